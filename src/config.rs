@@ -0,0 +1,988 @@
+//! Builds a [`Logger`](../struct.Logger.html) from a TOML or JSON config
+//! document.
+//!
+//! A document sets the base `level`, per-subsystem `levels`, the initial
+//! `context` and a list of `targets`. Every field is optional. Without any
+//! targets messages are written to a [`Console`](../targets/struct.Console.html)
+//! with default options.
+//!
+//! ```toml
+//! level = "info"
+//!
+//! # Messages whose `src` is `db`, or starts with `db::`, are logged at
+//! # trace. `filter_key` changes the context key names are matched against.
+//! [levels]
+//! db = "trace"
+//! "http::client" = "warn"
+//!
+//! [context]
+//! service = "billing"
+//! port = 8080
+//!
+//! [[targets]]
+//! type = "console"
+//! stream = "split"             # stdout, stderr or split
+//! color = "auto"               # auto, always or never
+//! show_timestamp = true        # show_* default to true
+//! theme = "light"              # dark, light, monochrome or a theme config
+//! # template = "{level:>7|level} {message} {ctx}"
+//!
+//! [[targets]]
+//! type = "json_file"
+//! path = "/var/log/billing.json"
+//! precision = "micros"         # seconds, millis, micros or nanos
+//! timezone = "utc"             # utc or local
+//! show_hostname = true         # also show_timestamp, show_pid, show_thread
+//! line_flush = false           # also buffer_capacity
+//!
+//! [[targets]]
+//! type = "logfmt"
+//! stream = "stderr"            # or path = "..."; stdout by default
+//!
+//! [[targets]]
+//! type = "rolling_file"
+//! path = "/var/log/billing.log"
+//! max_size = 10485760
+//! period = "daily"             # hourly or daily
+//! keep = 7
+//! format = "json"              # json, logfmt or text
+//!
+//! [[targets]]
+//! type = "syslog"
+//! transport = "udp"            # unix, udp or tcp
+//! address = "logs.internal:514"
+//! framing = "octet_counting"   # octet_counting or newline, for tcp
+//! format = "rfc5424"           # rfc5424 or rfc3164
+//! facility = "local0"
+//! # path, hostname, app_name, procid and sd_id are also accepted.
+//! ```
+//!
+//! The same document in JSON has the same keys, with `targets` as an array
+//! of objects.
+//!
+//! Errors in the document are reported with the line they were found on.
+//! Errors within a target are reported on the line the target starts on.
+//!
+//! # Environment overrides
+//!
+//! [`Config::env`](struct.Config.html#method.env) applies overrides from
+//! environment variables starting with a prefix, `QUIL` by default:
+//!
+//! * `QUIL_LEVEL` - Replaces the base level.
+//! * `QUIL_LEVELS` - Comma separated `name=level` pairs added to the
+//!   per-subsystem levels, such as `db=debug,http=warn`.
+//! * `QUIL_CONTEXT_<KEY>` - Sets the context key `<key>`, in lower case, to
+//!   a string.
+//!
+//! # Reloading
+//!
+//! [`watch`](fn.watch.html) reapplies a config file to a running logger
+//! whenever the file changes or, with the `signals` feature, the process
+//! receives `SIGHUP`, keeping the previous config if the new one is
+//! invalid.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # fn main() {
+//! use quil::config::Config;
+//!
+//! let config = Config::from_toml(r#"
+//!   level = "info"
+//!
+//!   [levels]
+//!   db = "trace"
+//!
+//!   [context]
+//!   service = "billing"
+//!
+//!   [[targets]]
+//!   type = "console"
+//!   stream = "stderr"
+//! "#).unwrap();
+//!
+//! let logger = config.env("BILLING_LOG").unwrap().build().unwrap();
+//! logger.info("started");
+//!
+//! let error = Config::from_toml("level = \"info\"\n\n[[targets]]\ntype = \"tape\"\n").err().unwrap();
+//! assert_eq!(error.line(), Some(4));
+//! # }
+//! ```
+
+mod watch;
+
+pub use self::watch::{watch, WatchOptions, Watcher};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use super::Context;
+use super::Filter;
+use super::Level;
+use super::Logger;
+use super::Value;
+use super::formatters::{self, Json, Precision, Template, Text, Theme, Timezone};
+use super::targets::{
+  ColorMode, Console, ConsoleOptions, Facility, Framing, JsonFileOptions, LogfmtOptions, Period,
+  RollingFileOptions, Stream, SyslogFormat, SyslogOptions, TargetSet, Transport,
+};
+
+/// Loads a logger from a TOML or JSON config file, applying overrides from
+/// `QUIL_` environment variables. See [`Config`](struct.Config.html).
+///
+/// # Arguments
+///
+/// * `path` - The path of the config file.
+pub fn load<P>(path: P) -> Result<Logger, ConfigError>
+where
+  P: AsRef<Path>,
+{
+  Config::from_file(path)?.env("QUIL")?.build()
+}
+
+/// A parsed logger config document. See the [module docs](index.html) for
+/// the format.
+#[derive(Debug, Clone)]
+pub struct Config {
+  level: Option<Level>,
+  filter_key: Option<String>,
+  levels: Vec<(String, Level)>,
+  context: Context,
+  targets: Option<Vec<TargetEntry>>,
+}
+
+impl Config {
+  /// Parses a TOML config document.
+  ///
+  /// # Arguments
+  ///
+  /// * `text` - The document.
+  pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+    match toml::from_str::<Document>(text) {
+      Ok(document) => {
+        let mut config = Config::from(document);
+        if let Ok(spans) = toml::from_str::<TomlTargetSpans>(text) {
+          let starts = spans.targets.unwrap_or_default();
+          config.set_target_lines(text, starts.iter().map(|target| target.span().start));
+        }
+        Ok(config)
+      }
+      Err(error) => {
+        let line = error.span().map(|span| line_of(text, span.start));
+        Err(ConfigError::new(line, error.message().to_string()))
+      }
+    }
+  }
+
+  /// Parses a JSON config document.
+  ///
+  /// # Arguments
+  ///
+  /// * `text` - The document.
+  pub fn from_json(text: &str) -> Result<Self, ConfigError> {
+    match serde_json::from_str::<Document>(text) {
+      Ok(document) => {
+        let mut config = Config::from(document);
+        if let Ok(spans) = serde_json::from_str::<JsonTargetSpans>(text) {
+          let starts = spans.targets.unwrap_or_default();
+          let offset = |target: &&RawValue| target.get().as_ptr() as usize - text.as_ptr() as usize;
+          config.set_target_lines(text, starts.iter().map(offset));
+        }
+        Ok(config)
+      }
+      Err(error) => {
+        let line = if error.line() > 0 { Some(error.line()) } else { None };
+        let reason = error.to_string();
+        // serde_json appends the position to the message.
+        let reason = match reason.rfind(" at line ") {
+          Some(index) => reason[..index].to_string(),
+          None => reason,
+        };
+        Err(ConfigError::new(line, reason))
+      }
+    }
+  }
+
+  /// Reads and parses a config file. Files ending in `.json` are parsed as
+  /// JSON, and anything else as TOML.
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - The path of the config file.
+  pub fn from_file<P>(path: P) -> Result<Self, ConfigError>
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|error| {
+      ConfigError::new(None, format!("failed to read {}: {}", path.display(), error))
+    })?;
+    if path.extension().is_some_and(|extension| extension == "json") {
+      Self::from_json(&text)
+    } else {
+      Self::from_toml(&text)
+    }
+  }
+
+  /// Applies overrides from environment variables starting with `prefix`
+  /// followed by `_`. See the [module docs](index.html#environment-overrides).
+  ///
+  /// # Arguments
+  ///
+  /// * `prefix` - The prefix of the variables, such as `QUIL`.
+  pub fn env(mut self, prefix: &str) -> Result<Self, ConfigError> {
+    let var = |name: &str| format!("{}_{}", prefix, name);
+
+    if let Some(level) = env::var_os(var("LEVEL")) {
+      let level = level.to_string_lossy();
+      self.level = Some(
+        level
+          .parse()
+          .map_err(|error| ConfigError::env(&var("LEVEL"), format!("{}", error)))?,
+      );
+    }
+
+    if let Some(levels) = env::var_os(var("LEVELS")) {
+      for pair in levels.to_string_lossy().split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let mut parts = pair.splitn(2, '=');
+        let name = parts.next().unwrap_or("").trim();
+        let level = match parts.next() {
+          Some(level) if !name.is_empty() => level
+            .parse()
+            .map_err(|error| ConfigError::env(&var("LEVELS"), format!("{}", error)))?,
+          _ => {
+            return Err(ConfigError::env(
+              &var("LEVELS"),
+              format!("expected `name=level`, found `{}`", pair),
+            ))
+          }
+        };
+        self.levels.retain(|entry| entry.0 != name);
+        self.levels.push((name.to_string(), level));
+      }
+    }
+
+    let context_prefix = var("CONTEXT_");
+    for (name, value) in env::vars_os() {
+      let name = name.to_string_lossy();
+      if name.starts_with(&context_prefix) && name.len() > context_prefix.len() {
+        let key = name[context_prefix.len()..].to_lowercase();
+        self.context.set(key, value.to_string_lossy().into_owned());
+      }
+    }
+    Ok(self)
+  }
+
+  /// Returns the base level, if the document sets one.
+  pub fn level(&self) -> Option<Level> {
+    self.level
+  }
+
+  /// Returns a filter applying the per-subsystem levels, if the document
+  /// sets any.
+  pub fn filter(&self) -> Option<Filter> {
+    if self.levels.is_empty() {
+      return None;
+    }
+    let mut filter = Filter::new();
+    if let Some(ref key) = self.filter_key {
+      filter = filter.key(key.clone());
+    }
+    for &(ref name, level) in &self.levels {
+      filter = filter.directive(name.clone(), level);
+    }
+    Some(filter)
+  }
+
+  /// Returns the initial context.
+  pub fn context(&self) -> &Context {
+    &self.context
+  }
+
+  /// Creates the targets the document names, opening any files and
+  /// sockets.
+  pub fn build_targets(&self) -> Result<TargetSet<'static>, ConfigError> {
+    let mut targets = TargetSet::new();
+    match self.targets {
+      Some(ref entries) => {
+        for (index, entry) in entries.iter().enumerate() {
+          let config = &entry.config;
+          config.add_to(&mut targets).map_err(|error| {
+            ConfigError::new(
+              entry.line,
+              format!("failed to open target {} ({}): {}", index + 1, config.name(), error),
+            )
+          })?;
+        }
+      }
+      None => targets.add(Console::new()),
+    }
+    Ok(targets)
+  }
+
+  /// Creates a logger from the config.
+  pub fn build(&self) -> Result<Logger, ConfigError> {
+    let logger = Logger::new(self.build_targets()?, self.context.clone());
+    if let Some(level) = self.level {
+      logger.set_level(level);
+    }
+    if let Some(filter) = self.filter() {
+      logger.set_filter(filter);
+    }
+    Ok(logger)
+  }
+
+  /// Changes the level, filter and targets of an existing logger, and of
+  /// every logger sharing its ancestor, to the ones in the config. See
+  /// [`Logger::reconfigure`](../struct.Logger.html#method.reconfigure).
+  ///
+  /// The logger is left unchanged if the targets cannot be created. The
+  /// context of loggers cannot be changed, so the config's context is not
+  /// applied. A config without a level sets the level to
+  /// [`Trace`](../enum.Level.html#variant.Trace), as for new loggers.
+  ///
+  /// # Arguments
+  ///
+  /// * `logger` - The logger to change.
+  pub fn apply(&self, logger: &Logger) -> Result<(), ConfigError> {
+    let targets = self.build_targets()?;
+    logger.reconfigure(self.level.unwrap_or(Level::Trace), self.filter(), targets);
+    Ok(())
+  }
+
+  // Records the line each target starts on, given the byte offset of each
+  // target within the document.
+  fn set_target_lines<I>(&mut self, text: &str, starts: I)
+  where
+    I: Iterator<Item = usize>,
+  {
+    if let Some(ref mut entries) = self.targets {
+      for (entry, start) in entries.iter_mut().zip(starts) {
+        entry.line = Some(line_of(text, start));
+      }
+    }
+  }
+}
+
+// The line of the document a byte offset is on, starting at 1.
+fn line_of(text: &str, offset: usize) -> usize {
+  text[..offset].matches('\n').count() + 1
+}
+
+/// The error returned when a config document is invalid, or the logger it
+/// describes cannot be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+  line: Option<usize>,
+  variable: Option<String>,
+  reason: String,
+}
+
+impl ConfigError {
+  fn new(line: Option<usize>, reason: String) -> Self {
+    Self {
+      line,
+      variable: None,
+      reason,
+    }
+  }
+
+  fn env(variable: &str, reason: String) -> Self {
+    Self {
+      line: None,
+      variable: Some(variable.to_string()),
+      reason,
+    }
+  }
+
+  /// Returns the line of the document the error is on, starting at 1, if
+  /// the error is in the document.
+  pub fn line(&self) -> Option<usize> {
+    self.line
+  }
+
+  /// Returns the environment variable the error is in, if any.
+  pub fn variable(&self) -> Option<&str> {
+    self.variable.as_deref()
+  }
+}
+
+impl fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match (self.line, &self.variable) {
+      (Some(line), _) => write!(f, "invalid config on line {}: {}", line, self.reason),
+      (None, Some(variable)) => write!(f, "invalid config in {}: {}", variable, self.reason),
+      (None, None) => write!(f, "invalid config: {}", self.reason),
+    }
+  }
+}
+
+impl error::Error for ConfigError {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+  level: Option<LevelName>,
+  filter_key: Option<String>,
+  #[serde(default)]
+  levels: BTreeMap<String, LevelName>,
+  #[serde(default)]
+  context: BTreeMap<String, ContextValue>,
+  targets: Option<Vec<TargetConfig>>,
+}
+
+impl From<Document> for Config {
+  fn from(document: Document) -> Self {
+    let mut context = Context::new();
+    for (key, value) in document.context {
+      context.set(key, value.0);
+    }
+    Config {
+      level: document.level.map(|level| level.0),
+      filter_key: document.filter_key,
+      levels: document
+        .levels
+        .into_iter()
+        .map(|(name, level)| (name, level.0))
+        .collect(),
+      context,
+      targets: document.targets.map(|targets| {
+        targets
+          .into_iter()
+          .map(|config| TargetEntry { config, line: None })
+          .collect()
+      }),
+    }
+  }
+}
+
+// Only the targets of a document, each with its position, so that errors
+// opening a target can be reported on the line it starts on.
+#[derive(Deserialize)]
+struct TomlTargetSpans {
+  targets: Option<Vec<toml::Spanned<toml::Table>>>,
+}
+
+#[derive(Deserialize)]
+struct JsonTargetSpans<'a> {
+  #[serde(borrow)]
+  targets: Option<Vec<&'a RawValue>>,
+}
+
+// A target and the line of the document it starts on.
+#[derive(Debug, Clone)]
+struct TargetEntry {
+  config: TargetConfig,
+  line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TargetConfig {
+  Console(ConsoleConfig),
+  JsonFile(JsonFileConfig),
+  Logfmt(LogfmtConfig),
+  RollingFile(RollingFileConfig),
+  Syslog(SyslogConfig),
+}
+
+impl TargetConfig {
+  fn name(&self) -> &'static str {
+    match *self {
+      TargetConfig::Console(_) => "console",
+      TargetConfig::JsonFile(_) => "json_file",
+      TargetConfig::Logfmt(_) => "logfmt",
+      TargetConfig::RollingFile(_) => "rolling_file",
+      TargetConfig::Syslog(_) => "syslog",
+    }
+  }
+
+  fn add_to(&self, targets: &mut TargetSet<'static>) -> Result<(), io::Error> {
+    match *self {
+      TargetConfig::Console(ref config) => targets.add(config.build()),
+      TargetConfig::JsonFile(ref config) => targets.add(config.build()?),
+      TargetConfig::Logfmt(ref config) => config.add_to(targets)?,
+      TargetConfig::RollingFile(ref config) => targets.add(config.build()?),
+      TargetConfig::Syslog(ref config) => targets.add(config.build()?),
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConsoleConfig {
+  stream: Option<StreamName>,
+  color: Option<ColorName>,
+  show_timestamp: Option<bool>,
+  show_level: Option<bool>,
+  show_context: Option<bool>,
+  theme: Option<ThemeConfig>,
+  template: Option<TemplateConfig>,
+}
+
+impl ConsoleConfig {
+  fn build(&self) -> Console {
+    let theme = self.theme.map_or_else(Theme::dark, |theme| theme.0);
+    let mut options = ConsoleOptions::new()
+      .show_timestamp(self.show_timestamp.unwrap_or(true))
+      .show_level(self.show_level.unwrap_or(true))
+      .show_context(self.show_context.unwrap_or(true))
+      .theme(theme);
+    if let Some(ref template) = self.template {
+      options = options.formatter(template.0.clone().colored(true).theme(theme));
+    }
+    if let Some(ref stream) = self.stream {
+      options = options.stream(stream.0);
+    }
+    if let Some(ref color) = self.color {
+      options = options.color(color.0);
+    }
+    options.build()
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonFileConfig {
+  path: PathBuf,
+  show_timestamp: Option<bool>,
+  precision: Option<PrecisionName>,
+  timezone: Option<TimezoneName>,
+  show_hostname: Option<bool>,
+  show_pid: Option<bool>,
+  show_thread: Option<bool>,
+  buffer_capacity: Option<usize>,
+  line_flush: Option<bool>,
+}
+
+impl JsonFileConfig {
+  fn build(&self) -> Result<super::targets::JsonFile, io::Error> {
+    let mut options = JsonFileOptions::new(self.path.clone());
+    if let Some(show_timestamp) = self.show_timestamp {
+      options = options.show_timestamp(show_timestamp);
+    }
+    if let Some(ref precision) = self.precision {
+      options = options.precision(precision.0);
+    }
+    if let Some(ref timezone) = self.timezone {
+      options = options.timezone(timezone.0);
+    }
+    if let Some(show_hostname) = self.show_hostname {
+      options = options.show_hostname(show_hostname);
+    }
+    if let Some(show_pid) = self.show_pid {
+      options = options.show_pid(show_pid);
+    }
+    if let Some(show_thread) = self.show_thread {
+      options = options.show_thread(show_thread);
+    }
+    if let Some(buffer_capacity) = self.buffer_capacity {
+      options = options.buffer_capacity(buffer_capacity);
+    }
+    if let Some(line_flush) = self.line_flush {
+      options = options.line_flush(line_flush);
+    }
+    options.build()
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogfmtConfig {
+  path: Option<PathBuf>,
+  stream: Option<OutputName>,
+  show_timestamp: Option<bool>,
+  precision: Option<PrecisionName>,
+  timezone: Option<TimezoneName>,
+  buffer_capacity: Option<usize>,
+  line_flush: Option<bool>,
+}
+
+impl LogfmtConfig {
+  fn add_to(&self, targets: &mut TargetSet<'static>) -> Result<(), io::Error> {
+    let mut options = LogfmtOptions::new();
+    if let Some(show_timestamp) = self.show_timestamp {
+      options = options.show_timestamp(show_timestamp);
+    }
+    if let Some(ref precision) = self.precision {
+      options = options.precision(precision.0);
+    }
+    if let Some(ref timezone) = self.timezone {
+      options = options.timezone(timezone.0);
+    }
+    if let Some(buffer_capacity) = self.buffer_capacity {
+      options = options.buffer_capacity(buffer_capacity);
+    }
+    if let Some(line_flush) = self.line_flush {
+      options = options.line_flush(line_flush);
+    }
+    match (&self.path, self.stream.as_ref().map(|stream| stream.0)) {
+      (Some(path), _) => {
+        targets.add(options.build(OpenOptions::new().create(true).append(true).open(path)?))
+      }
+      (None, Some(Stream::Stderr)) => targets.add(options.build(io::stderr())),
+      (None, _) => targets.add(options.build(io::stdout())),
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RollingFileConfig {
+  path: PathBuf,
+  max_size: Option<u64>,
+  period: Option<PeriodName>,
+  keep: Option<usize>,
+  format: Option<FormatName>,
+}
+
+impl RollingFileConfig {
+  fn build(&self) -> Result<super::targets::RollingFile, io::Error> {
+    let mut options = RollingFileOptions::new(self.path.clone());
+    if let Some(max_size) = self.max_size {
+      options = options.max_size(max_size);
+    }
+    if let Some(ref period) = self.period {
+      options = options.period(period.0);
+    }
+    if let Some(keep) = self.keep {
+      options = options.keep(keep);
+    }
+    options = match self.format.as_ref().map(|format| format.0) {
+      Some(Format::Logfmt) => options.formatter(formatters::Logfmt::new()),
+      Some(Format::Text) => options.formatter(Text::new()),
+      Some(Format::Json) | None => options.formatter(Json::new()),
+    };
+    options.build()
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SyslogConfig {
+  transport: Option<TransportName>,
+  path: Option<PathBuf>,
+  address: Option<String>,
+  framing: Option<FramingName>,
+  format: Option<SyslogFormatName>,
+  facility: Option<FacilityName>,
+  hostname: Option<String>,
+  app_name: Option<String>,
+  procid: Option<String>,
+  sd_id: Option<String>,
+}
+
+impl SyslogConfig {
+  fn build(&self) -> Result<super::targets::Syslog, io::Error> {
+    let mut options = SyslogOptions::new();
+    let transport = self.transport.as_ref().map_or(TransportKind::Unix, |transport| transport.0);
+    options = match transport {
+      TransportKind::Unix => match self.path {
+        Some(ref path) => options.transport(Transport::Unix(path.clone())),
+        None => options,
+      },
+      TransportKind::Udp => options.transport(Transport::Udp(self.resolve()?)),
+      TransportKind::Tcp => options.transport(Transport::Tcp(self.resolve()?)),
+    };
+    if let Some(ref framing) = self.framing {
+      options = options.framing(framing.0);
+    }
+    if let Some(ref format) = self.format {
+      options = options.format(format.0);
+    }
+    if let Some(ref facility) = self.facility {
+      options = options.facility(facility.0);
+    }
+    if let Some(ref hostname) = self.hostname {
+      options = options.hostname(hostname.clone());
+    }
+    if let Some(ref app_name) = self.app_name {
+      options = options.app_name(app_name.clone());
+    }
+    if let Some(ref procid) = self.procid {
+      options = options.procid(procid.clone());
+    }
+    if let Some(ref sd_id) = self.sd_id {
+      options = options.sd_id(sd_id.clone());
+    }
+    options.build()
+  }
+
+  fn resolve(&self) -> Result<::std::net::SocketAddr, io::Error> {
+    let address = self.address.as_ref().ok_or_else(|| {
+      io::Error::new(io::ErrorKind::InvalidInput, "an address is required for udp and tcp")
+    })?;
+    address.to_socket_addrs()?.next().ok_or_else(|| {
+      io::Error::new(io::ErrorKind::NotFound, format!("{} did not resolve", address))
+    })
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+  Json,
+  Logfmt,
+  Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransportKind {
+  Unix,
+  Udp,
+  Tcp,
+}
+
+// Defines a wrapper around a type which is deserialized from one of a fixed
+// set of names.
+macro_rules! named {
+  ($name:ident: $ty:ty { $($text:expr => $value:expr),+ $(,)? }) => {
+    #[derive(Debug, Clone)]
+    struct $name($ty);
+
+    impl<'de> Deserialize<'de> for $name {
+      fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+      where
+        D: Deserializer<'de>,
+      {
+        const NAMES: &[&str] = &[$($text),+];
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+          $($text => Ok($name($value)),)+
+          _ => Err(de::Error::unknown_variant(&name, NAMES)),
+        }
+      }
+    }
+  };
+}
+
+named!(StreamName: Stream {
+  "stdout" => Stream::Stdout,
+  "stderr" => Stream::Stderr,
+  "split" => Stream::Split,
+});
+
+named!(OutputName: Stream {
+  "stdout" => Stream::Stdout,
+  "stderr" => Stream::Stderr,
+});
+
+named!(ColorName: ColorMode {
+  "auto" => ColorMode::Auto,
+  "always" => ColorMode::Always,
+  "never" => ColorMode::Never,
+});
+
+named!(PrecisionName: Precision {
+  "seconds" => Precision::Seconds,
+  "millis" => Precision::Millis,
+  "micros" => Precision::Micros,
+  "nanos" => Precision::Nanos,
+});
+
+named!(TimezoneName: Timezone {
+  "utc" => Timezone::Utc,
+  "local" => Timezone::Local,
+});
+
+named!(PeriodName: Period {
+  "hourly" => Period::Hourly,
+  "daily" => Period::Daily,
+});
+
+named!(FormatName: Format {
+  "json" => Format::Json,
+  "logfmt" => Format::Logfmt,
+  "text" => Format::Text,
+});
+
+named!(TransportName: TransportKind {
+  "unix" => TransportKind::Unix,
+  "udp" => TransportKind::Udp,
+  "tcp" => TransportKind::Tcp,
+});
+
+named!(FramingName: Framing {
+  "octet_counting" => Framing::OctetCounting,
+  "newline" => Framing::Newline,
+});
+
+named!(SyslogFormatName: SyslogFormat {
+  "rfc5424" => SyslogFormat::Rfc5424,
+  "rfc3164" => SyslogFormat::Rfc3164,
+});
+
+named!(FacilityName: Facility {
+  "kern" => Facility::Kern,
+  "user" => Facility::User,
+  "mail" => Facility::Mail,
+  "daemon" => Facility::Daemon,
+  "auth" => Facility::Auth,
+  "syslog" => Facility::Syslog,
+  "lpr" => Facility::Lpr,
+  "news" => Facility::News,
+  "uucp" => Facility::Uucp,
+  "cron" => Facility::Cron,
+  "authpriv" => Facility::Authpriv,
+  "ftp" => Facility::Ftp,
+  "local0" => Facility::Local0,
+  "local1" => Facility::Local1,
+  "local2" => Facility::Local2,
+  "local3" => Facility::Local3,
+  "local4" => Facility::Local4,
+  "local5" => Facility::Local5,
+  "local6" => Facility::Local6,
+  "local7" => Facility::Local7,
+});
+
+/// A level given by name or as a number from 0 to 5.
+struct LevelName(Level);
+
+impl<'de> Deserialize<'de> for LevelName {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    struct LevelVisitor;
+
+    impl<'de> Visitor<'de> for LevelVisitor {
+      type Value = LevelName;
+
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a level name or a number from 0 to 5")
+      }
+
+      fn visit_str<E>(self, value: &str) -> Result<LevelName, E>
+      where
+        E: de::Error,
+      {
+        value.parse().map(LevelName).map_err(E::custom)
+      }
+
+      fn visit_u64<E>(self, value: u64) -> Result<LevelName, E>
+      where
+        E: de::Error,
+      {
+        self.visit_str(&value.to_string())
+      }
+
+      fn visit_i64<E>(self, value: i64) -> Result<LevelName, E>
+      where
+        E: de::Error,
+      {
+        self.visit_str(&value.to_string())
+      }
+    }
+
+    deserializer.deserialize_any(LevelVisitor)
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ThemeConfig(Theme);
+
+impl<'de> Deserialize<'de> for ThemeConfig {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let theme = String::deserialize(deserializer)?;
+    match theme.trim() {
+      "dark" => Ok(ThemeConfig(Theme::dark())),
+      "light" => Ok(ThemeConfig(Theme::light())),
+      "monochrome" => Ok(ThemeConfig(Theme::monochrome())),
+      _ => Theme::parse(&theme).map(ThemeConfig).map_err(de::Error::custom),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct TemplateConfig(Template);
+
+impl<'de> Deserialize<'de> for TemplateConfig {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let template = String::deserialize(deserializer)?;
+    Template::parse(&template)
+      .map(TemplateConfig)
+      .map_err(de::Error::custom)
+  }
+}
+
+struct ContextValue(Value);
+
+impl<'de> Deserialize<'de> for ContextValue {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    struct ValueVisitor;
+
+    impl<'de> Visitor<'de> for ValueVisitor {
+      type Value = ContextValue;
+
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a context value")
+      }
+
+      fn visit_bool<E>(self, value: bool) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::Bool(value)))
+      }
+
+      fn visit_i64<E>(self, value: i64) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::I64(value)))
+      }
+
+      fn visit_u64<E>(self, value: u64) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::U64(value)))
+      }
+
+      fn visit_f64<E>(self, value: f64) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::F64(value)))
+      }
+
+      fn visit_str<E>(self, value: &str) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::String(value.to_string())))
+      }
+
+      fn visit_unit<E>(self) -> Result<ContextValue, E> {
+        Ok(ContextValue(Value::Null))
+      }
+
+      fn visit_seq<A>(self, mut seq: A) -> Result<ContextValue, A::Error>
+      where
+        A: SeqAccess<'de>,
+      {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element::<ContextValue>()? {
+          values.push(value.0);
+        }
+        Ok(ContextValue(Value::Array(values)))
+      }
+
+      fn visit_map<A>(self, mut map: A) -> Result<ContextValue, A::Error>
+      where
+        A: MapAccess<'de>,
+      {
+        let mut values = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry::<String, ContextValue>()? {
+          values.insert(key, value.0);
+        }
+        Ok(ContextValue(Value::Map(values)))
+      }
+    }
+
+    deserializer.deserialize_any(ValueVisitor)
+  }
+}
@@ -0,0 +1,250 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+#[cfg(all(unix, feature = "signals"))]
+use signal_hook::{consts::SIGHUP, flag, low_level, SigId};
+use super::super::Context;
+use super::super::Logger;
+use super::{Config, ConfigError};
+
+/// Contains configuration options for a [`Watcher`](struct.Watcher.html).
+///
+/// By default the file is checked for changes every second, the config is
+/// also reloaded when the process receives `SIGHUP` if the `signals`
+/// feature is enabled, and overrides from `QUIL_` environment variables are
+/// applied to each reloaded config.
+pub struct WatchOptions {
+  interval: Duration,
+  #[cfg_attr(not(all(unix, feature = "signals")), allow(dead_code))]
+  signal: bool,
+  env: bool,
+  env_prefix: String,
+}
+
+impl WatchOptions {
+  /// Creates a new instance of `WatchOptions`.
+  pub fn new() -> Self {
+    Self {
+      interval: Duration::from_secs(1),
+      signal: true,
+      env: true,
+      env_prefix: "QUIL".to_string(),
+    }
+  }
+
+  /// Set how often the file is checked for changes. Reloads triggered by
+  /// `SIGHUP` also happen on the next check.
+  pub fn interval(mut self, interval: Duration) -> Self {
+    self.interval = interval;
+    self
+  }
+
+  /// Enable/disable reloading the config when the process receives
+  /// `SIGHUP`. This has no effect without the `signals` feature, or on
+  /// platforms without signals.
+  pub fn signal(mut self, signal: bool) -> Self {
+    self.signal = signal;
+    self
+  }
+
+  /// Enable/disable applying overrides from environment variables to each
+  /// reloaded config. See [`Config::env`](struct.Config.html#method.env).
+  pub fn env(mut self, env: bool) -> Self {
+    self.env = env;
+    self
+  }
+
+  /// Set the prefix of the environment variables overrides are read from.
+  pub fn env_prefix<S>(mut self, env_prefix: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.env_prefix = env_prefix.into();
+    self
+  }
+
+  /// Starts watching a config file, applying it to the logger each time it
+  /// changes. See [`watch`](fn.watch.html).
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - The path of the config file.
+  /// * `logger` - The logger to apply the config to.
+  pub fn watch<P>(self, path: P, logger: Logger) -> Result<Watcher, io::Error>
+  where
+    P: AsRef<Path>,
+  {
+    let shared = Arc::new(Shared {
+      path: path.as_ref().to_path_buf(),
+      logger,
+      env_prefix: if self.env { Some(self.env_prefix) } else { None },
+    });
+
+    let hangup = Arc::new(AtomicBool::new(false));
+    #[cfg(all(unix, feature = "signals"))]
+    let signal = if self.signal {
+      Some(flag::register(SIGHUP, hangup.clone())?)
+    } else {
+      None
+    };
+
+    let (stop, stopped) = mpsc::channel::<()>();
+    let worker_shared = shared.clone();
+    let interval = self.interval;
+    let mut stamp = stamp_of(&shared.path);
+    let spawned = thread::Builder::new()
+      .name("quil-config-watch".to_string())
+      .spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+          let current = stamp_of(&worker_shared.path);
+          let changed = current.is_some() && current != stamp;
+          stamp = current;
+          if hangup.swap(false, Ordering::SeqCst) || changed {
+            worker_shared.reload_or_report();
+          }
+        }
+      });
+    let worker = match spawned {
+      Ok(worker) => worker,
+      Err(error) => {
+        #[cfg(all(unix, feature = "signals"))]
+        {
+          if let Some(signal) = signal {
+            low_level::unregister(signal);
+          }
+        }
+        return Err(error);
+      }
+    };
+
+    Ok(Watcher {
+      shared,
+      stop: Some(stop),
+      worker: Some(worker),
+      #[cfg(all(unix, feature = "signals"))]
+      signal,
+    })
+  }
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Watches a config file, applying it to a logger, and to every logger
+/// sharing its ancestor, each time the file changes or, with the `signals`
+/// feature, the process receives `SIGHUP`. Use
+/// [`WatchOptions`](struct.WatchOptions.html) to change how often the file
+/// is checked.
+///
+/// The level, filter and targets are swapped together with
+/// [`Config::apply`](struct.Config.html#method.apply). If the changed file
+/// cannot be read, is invalid or its targets cannot be created, the logger
+/// keeps its previous config and an error is logged to it, with the path
+/// under the `config` key and the reason under the `error` key.
+///
+/// Watching stops when the returned [`Watcher`](struct.Watcher.html) is
+/// dropped.
+///
+/// # Arguments
+///
+/// * `path` - The path of the config file.
+/// * `logger` - The logger to apply the config to.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # fn main() {
+/// let logger = quil::config::load("logging.toml").unwrap();
+/// let _watcher = quil::config::watch("logging.toml", logger.clone()).unwrap();
+///
+/// logger.info("started");
+/// # }
+/// ```
+pub fn watch<P>(path: P, logger: Logger) -> Result<Watcher, io::Error>
+where
+  P: AsRef<Path>,
+{
+  WatchOptions::new().watch(path, logger)
+}
+
+/// Applies a config file to a logger each time it changes. Returned by
+/// [`watch`](fn.watch.html). Watching stops when the `Watcher` is dropped.
+pub struct Watcher {
+  shared: Arc<Shared>,
+  stop: Option<Sender<()>>,
+  worker: Option<JoinHandle<()>>,
+  #[cfg(all(unix, feature = "signals"))]
+  signal: Option<SigId>,
+}
+
+impl Watcher {
+  /// Reloads the config file now. Unlike reloads triggered by a change, a
+  /// failure is returned rather than logged. The logger keeps its previous
+  /// config if the reload fails.
+  pub fn reload(&self) -> Result<(), ConfigError> {
+    self.shared.reload()
+  }
+
+  /// Returns the path of the config file being watched.
+  pub fn path(&self) -> &Path {
+    &self.shared.path
+  }
+}
+
+impl Drop for Watcher {
+  fn drop(&mut self) {
+    #[cfg(all(unix, feature = "signals"))]
+    {
+      if let Some(signal) = self.signal.take() {
+        low_level::unregister(signal);
+      }
+    }
+    self.stop.take();
+    if let Some(worker) = self.worker.take() {
+      let _ = worker.join();
+    }
+  }
+}
+
+struct Shared {
+  path: PathBuf,
+  logger: Logger,
+  env_prefix: Option<String>,
+}
+
+impl Shared {
+  fn reload(&self) -> Result<(), ConfigError> {
+    let mut config = Config::from_file(&self.path)?;
+    if let Some(ref env_prefix) = self.env_prefix {
+      config = config.env(env_prefix)?;
+    }
+    config.apply(&self.logger)
+  }
+
+  fn reload_or_report(&self) {
+    if let Err(error) = self.reload() {
+      let mut context = Context::new();
+      context.set("config", self.path.display().to_string());
+      context.set("error", error.to_string());
+      self
+        .logger
+        .ctx(context)
+        .error("failed to reload the logger config, keeping the previous config");
+    }
+  }
+}
+
+// The modification time and length of the file, or `None` while it cannot
+// be read, such as while an editor replaces it.
+fn stamp_of(path: &Path) -> Option<(SystemTime, u64)> {
+  let metadata = fs::metadata(path).ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
@@ -1,5 +1,9 @@
+use std::any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error;
 use std::fmt;
 use std::collections::{hash_map, HashMap};
+use super::Value;
 
 /// Creates a [`Context`](struct.Context.html) from a varying set of
 /// keys and values.
@@ -10,8 +14,9 @@ use std::collections::{hash_map, HashMap};
 /// # #[macro_use] extern crate quil;
 /// # fn main() {
 /// # use quil::prelude::*;
-/// let ctx: Context = context!{ key: "value" };
+/// let ctx: Context = context!{ key: "value", retries: 3, ok: true };
 /// assert_eq!(ctx.get("key").unwrap(), "value");
+/// assert_eq!(ctx.get("retries").unwrap(), &Value::I64(3));
 /// }
 /// ```
 #[macro_export]
@@ -30,13 +35,14 @@ macro_rules! context {
 /// A logging context.
 ///
 /// Contexts contain meta data for the association with logged messages.
+/// Each key maps to a typed [`Value`](enum.Value.html).
 #[derive(Debug, Clone)]
-pub struct Context(HashMap<String, String>);
+pub struct Context(HashMap<String, Value>);
 
 impl Context {
   /// Creates a new context for use with a logger.
   pub fn new() -> Self {
-    Self { 0: HashMap::new() }
+    Context(HashMap::new())
   }
 
   /// Sets a value within the context by a given key.
@@ -44,7 +50,8 @@ impl Context {
   /// # Arguments
   ///
   /// * `key` - A key for the value.
-  /// * `value` - The value to add to the context.
+  /// * `value` - The value to add to the context. Anything that converts
+  ///   into a [`Value`](enum.Value.html) can be given.
   ///
   /// # Examples
   ///
@@ -54,6 +61,7 @@ impl Context {
   /// # use quil::prelude::*;
   /// # let mut ctx = Context::new();
   /// ctx.set("key", "value");
+  /// ctx.set("retries", 3);
   /// ```
   ///
   /// Clearing a value:
@@ -63,19 +71,80 @@ impl Context {
   /// # let mut ctx = Context::new();
   /// ctx.set("key", "");
   /// ```
-  pub fn set<S>(&mut self, key: S, value: S)
+  pub fn set<K, V>(&mut self, key: K, value: V)
   where
-    S: Into<String>,
+    K: Into<String>,
+    V: Into<Value>,
   {
     let key = key.into();
-    let value = value.into();
-    if value == "" {
-      self.0.remove(&key);
-    } else {
-      self.0.insert(key, value);
+    match value.into() {
+      Value::String(ref value) if value.is_empty() => {
+        self.0.remove(&key);
+      }
+      value => {
+        self.0.insert(key, value);
+      }
     }
   }
 
+  /// Creates a context describing an error, for logging the error in a
+  /// structured form rather than flattened into a string. See
+  /// [`Logger::error_with`](struct.Logger.html#method.error_with).
+  ///
+  /// The context has these keys:
+  ///
+  /// * `error` - The error's `Display` text.
+  /// * `error_type` - The name of the error's type, as given by
+  ///   [`type_name`](https://doc.rust-lang.org/std/any/fn.type_name.html),
+  ///   whose output may change between compiler versions. The name is only
+  ///   known for concrete types, so the key is left out when the error is a
+  ///   trait object such as `&dyn Error`, or a type holding one such as
+  ///   `Box<dyn Error>`.
+  /// * `error_chain` - An array of the `Display` text of each error in the
+  ///   error's [`source`](https://doc.rust-lang.org/std/error/trait.Error.html#method.source)
+  ///   chain, starting with its immediate source.
+  /// * `backtrace` - A backtrace of the call, only if `RUST_BACKTRACE` or
+  ///   `RUST_LIB_BACKTRACE` ask for one.
+  ///
+  /// # Arguments
+  ///
+  /// * `error` - The error to describe.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use quil::prelude::*;
+  /// let error = "x".parse::<u32>().unwrap_err();
+  /// let context = Context::from_error(&error);
+  ///
+  /// assert_eq!(context.get("error").unwrap(), "invalid digit found in string");
+  /// assert!(context.get("error_type").unwrap().as_str().unwrap().ends_with("ParseIntError"));
+  /// assert_eq!(context.get("error_chain").unwrap(), &Value::Array(vec![]));
+  /// ```
+  pub fn from_error<E>(error: &E) -> Self
+  where
+    E: error::Error + ?Sized,
+  {
+    let mut context = Context::new();
+    context.set("error", error.to_string());
+    let error_type = any::type_name::<E>();
+    if !error_type.contains("dyn ") {
+      context.set("error_type", error_type);
+    }
+    let mut chain = Vec::new();
+    let mut source = error.source();
+    while let Some(error) = source {
+      chain.push(Value::String(error.to_string()));
+      source = error.source();
+    }
+    context.set("error_chain", Value::Array(chain));
+    let backtrace = Backtrace::capture();
+    if backtrace.status() == BacktraceStatus::Captured {
+      context.set("backtrace", backtrace.to_string());
+    }
+    context
+  }
+
   /// Gets a value by key.
   ///
   /// If the key does not exist then None will be returned.
@@ -92,7 +161,7 @@ impl Context {
   /// ctx.set("key", "value");
   /// assert_eq!(ctx.get("key").unwrap(), "value");
   /// ```
-  pub fn get(&self, key: &str) -> Option<&String> {
+  pub fn get(&self, key: &str) -> Option<&Value> {
     self.0.get(key)
   }
 
@@ -120,7 +189,7 @@ impl Context {
   /// # }
   /// ```
   pub fn merge(&self, context: Self) -> Self {
-    let mut merged_context = Self { 0: self.0.clone() };
+    let mut merged_context = Context(self.0.clone());
     for (key, val) in context.0.into_iter() {
       merged_context.set(key, val);
     }
@@ -129,13 +198,24 @@ impl Context {
 
   /// Returns an iterator to iterate through the data contained within
   /// the context.
-  pub fn iter(&self) -> hash_map::Iter<String, String> {
+  pub fn iter(&self) -> hash_map::Iter<'_, String, Value> {
     self.0.iter()
   }
+}
+
+impl Default for Context {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Consumes the context returning an iterator to iterate through the
+/// data once contained within the context.
+impl IntoIterator for Context {
+  type Item = (String, Value);
+  type IntoIter = hash_map::IntoIter<String, Value>;
 
-  /// Consumes the context returning an iterator to iterate through the
-  /// data once contained within the context.
-  pub fn into_iter(self) -> hash_map::IntoIter<String, String> {
+  fn into_iter(self) -> Self::IntoIter {
     self.0.into_iter()
   }
 }
@@ -145,7 +225,10 @@ impl fmt::Display for Context {
     let messages: Vec<_> = self
       .0
       .iter()
-      .map(|(key, val)| format!("{}: \"{}\"", key, val))
+      .map(|(key, val)| match *val {
+        Value::String(ref val) => format!("{}: \"{}\"", key, val),
+        ref val => format!("{}: {}", key, val),
+      })
       .collect();
     write!(f, "{{ {} }}", messages.join(", "))
   }
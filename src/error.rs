@@ -0,0 +1,44 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// The error returned by a [`Target`](trait.Target.html) when it fails to
+/// log or flush a message.
+#[derive(Debug)]
+pub enum Error {
+  /// The target failed to write to its file, stream or socket.
+  Io(io::Error),
+  /// More than one target within a [`TargetSet`](targets/struct.TargetSet.html)
+  /// failed.
+  Targets(Vec<Error>),
+  /// The target failed for some other reason.
+  Other(String),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Error::Io(ref err) => write!(f, "{}", err),
+      Error::Targets(ref errs) => {
+        let errs: Vec<_> = errs.iter().map(|err| err.to_string()).collect();
+        write!(f, "{} targets failed: {}", errs.len(), errs.join("; "))
+      }
+      Error::Other(ref message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl error::Error for Error {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match *self {
+      Error::Io(ref err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(err: io::Error) -> Self {
+    Error::Io(err)
+  }
+}
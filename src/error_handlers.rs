@@ -0,0 +1,191 @@
+//! Contains error handlers and the error handler trait for deciding what
+//! happens when a target fails to log a message.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use super::Error;
+use super::Record;
+use super::Target;
+
+/// Structs that implement `ErrorHandler` decide what happens when a
+/// [`Logger`](../struct.Logger.html)'s target fails to log a message. They are
+/// set with [`Logger::set_error_handler`](../struct.Logger.html#method.set_error_handler).
+///
+/// Closures taking the same arguments as `handle` also implement
+/// `ErrorHandler`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::{Error, Record};
+/// let logger = Logger::new(Console::new(), context!{});
+/// logger.set_error_handler(|error: Error, record: &Record| {
+///   eprintln!("dropped {} message \"{}\": {}", record.level(), record.message(), error);
+/// });
+/// # }
+/// ```
+pub trait ErrorHandler {
+  /// Handles an error returned by a target.
+  ///
+  /// # Arguments
+  ///
+  /// * `error` - The error returned by the target.
+  /// * `record` - The record of the message that failed.
+  fn handle(&mut self, error: Error, record: &Record);
+}
+
+impl<F> ErrorHandler for F
+where
+  F: FnMut(Error, &Record),
+{
+  fn handle(&mut self, error: Error, record: &Record) {
+    self(error, record);
+  }
+}
+
+/// An error handler which silently discards errors.
+pub struct Ignore;
+
+impl ErrorHandler for Ignore {
+  fn handle(&mut self, _: Error, _: &Record) {}
+}
+
+/// An error handler which prints errors to stderr. This is the default
+/// error handler.
+pub struct Stderr;
+
+impl ErrorHandler for Stderr {
+  fn handle(&mut self, error: Error, record: &Record) {
+    eprintln!(
+      "quil: failed to log {} message \"{}\": {}",
+      record.level(),
+      record.message(),
+      error
+    );
+  }
+}
+
+/// An error handler which counts errors. Clones share the same count, so a
+/// clone can be kept to read it after the handler is given to a logger.
+///
+/// # Examples
+///
+/// A `TargetSet` keeps logging to its healthy targets when one fails:
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::{Error, Record};
+/// # use quil::targets::Target;
+/// # use std::fs;
+/// use quil::error_handlers::Count;
+///
+/// struct Broken;
+///
+/// impl Target for Broken {
+///   fn log(&mut self, _: &Record) -> Result<(), Error> {
+///     Err(Error::Other("disk full".to_string()))
+///   }
+/// }
+///
+/// # let path = std::env::temp_dir().join("quil_count_example.json");
+/// # let _ = fs::remove_file(&path);
+/// let count = Count::new();
+/// let logger = Logger::new(targets![Broken, JsonFile::open(path.clone()).unwrap()], context!{});
+/// logger.set_error_handler(count.clone());
+///
+/// logger.info("hello");
+///
+/// assert_eq!(count.count(), 1);
+/// assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Count(Arc<AtomicUsize>);
+
+impl Count {
+  /// Creates a new `Count` starting at zero.
+  pub fn new() -> Self {
+    Count(Arc::new(AtomicUsize::new(0)))
+  }
+
+  /// Returns the number of errors handled so far.
+  pub fn count(&self) -> usize {
+    self.0.load(Ordering::SeqCst)
+  }
+}
+
+impl ErrorHandler for Count {
+  fn handle(&mut self, _: Error, _: &Record) {
+    self.0.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
+/// An error handler which logs messages that failed to a fallback target.
+/// If the fallback target fails too, both errors are printed to stderr.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::{Error, Record};
+/// # use quil::targets::Target;
+/// use quil::error_handlers::Fallback;
+///
+/// struct Broken;
+///
+/// impl Target for Broken {
+///   fn log(&mut self, _: &Record) -> Result<(), Error> {
+///     Err(Error::Other("connection refused".to_string()))
+///   }
+/// }
+///
+/// let logger = Logger::new(Broken, context!{});
+/// logger.set_error_handler(Fallback::new(Console::new()));
+///
+/// logger.info("hello");
+/// # }
+/// ```
+pub struct Fallback<T>
+where
+  T: Target,
+{
+  target: T,
+}
+
+impl<T> Fallback<T>
+where
+  T: Target,
+{
+  /// Creates a new `Fallback` logging to the given target.
+  ///
+  /// # Arguments
+  ///
+  /// * `target` - The target failed messages will be logged to.
+  pub fn new(target: T) -> Self {
+    Self { target }
+  }
+}
+
+impl<T> ErrorHandler for Fallback<T>
+where
+  T: Target,
+{
+  fn handle(&mut self, error: Error, record: &Record) {
+    if let Err(fallback_error) = self.target.log(record) {
+      eprintln!(
+        "quil: failed to log {} message \"{}\": {}; fallback target failed: {}",
+        record.level(),
+        record.message(),
+        error,
+        fallback_error,
+      );
+    }
+  }
+}
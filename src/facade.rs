@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use log::{self, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use super::Context;
+use super::Level;
+use super::Logger;
+
+// The id of the installed logger, shared by its clones and sub-loggers, so
+// that `Logger::set_level` only touches `log`'s max level for that tree.
+static INSTALLED_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Installs a [`Logger`](../struct.Logger.html) as the global `log` crate
+/// logger. Records logged through `log`'s macros will be forwarded to the
+/// logger's target.
+///
+/// `log` has no equivalent of [`Verbose`](../enum.Level.html). Records from
+/// `log` are never logged at `Verbose`, and a logger set to `Verbose` lets
+/// `log`'s `info` records through while dropping `debug` and `trace`.
+///
+/// The `target`, `module_path`, `file` and `line` of each record are added
+/// to the context as `target`, `module`, `file` and `line`.
+///
+/// Changing the logger's level with
+/// [`Logger::set_level`](../struct.Logger.html#method.set_level) or its
+/// filter with [`Logger::set_filter`](../struct.Logger.html#method.set_filter)
+/// will update `log`'s max level.
+///
+/// Quil's logging macros have the same names as `log`'s, such as `info!`
+/// and `log!`. Only import one crate's macros with `#[macro_use]`, and call
+/// the other's by path, as in `log::info!` or `quil::info!`. Quil's macros
+/// can also be imported individually with `use quil::info;`.
+///
+/// # Arguments
+///
+/// * `logger` - The logger `log` records should be sent to.
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use] extern crate quil;
+/// extern crate log;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// quil::facade::install(logger.clone()).unwrap();
+///
+/// log::info!("hello from log");
+/// info!(logger, "hello from quil");
+///
+/// logger.set_level(Warn);
+/// assert_eq!(log::max_level(), log::LevelFilter::Warn);
+/// # }
+/// ```
+pub fn install(logger: Logger) -> Result<(), SetLoggerError> {
+  let level = logger.max_level();
+  let id = logger.id();
+  log::set_boxed_logger(Box::new(logger))?;
+  INSTALLED_ID.store(id, Ordering::SeqCst);
+  log::set_max_level(to_level_filter(level));
+  Ok(())
+}
+
+pub(crate) fn level_changed(id: usize, level: Level) {
+  if INSTALLED_ID.load(Ordering::SeqCst) == id {
+    log::set_max_level(to_level_filter(level));
+  }
+}
+
+fn to_level_filter(level: Level) -> LevelFilter {
+  match level {
+    Level::Error => LevelFilter::Error,
+    Level::Warn => LevelFilter::Warn,
+    Level::Info | Level::Verbose => LevelFilter::Info,
+    Level::Debug => LevelFilter::Debug,
+    Level::Trace => LevelFilter::Trace,
+  }
+}
+
+fn from_log_level(level: log::Level) -> Level {
+  match level {
+    log::Level::Error => Level::Error,
+    log::Level::Warn => Level::Warn,
+    log::Level::Info => Level::Info,
+    log::Level::Debug => Level::Debug,
+    log::Level::Trace => Level::Trace,
+  }
+}
+
+impl Log for Logger {
+  fn enabled(&self, metadata: &Metadata) -> bool {
+    // `log` only gives the target here, which defaults to the module path.
+    Logger::enabled(self, from_log_level(metadata.level()), Some(metadata.target()))
+  }
+
+  fn log(&self, record: &Record) {
+    let level = from_log_level(record.level());
+    if !Logger::enabled(self, level, record.module_path()) {
+      return;
+    }
+
+    let mut context = Context::new();
+    context.set("target", record.target());
+    if let Some(module_path) = record.module_path() {
+      context.set("module", module_path);
+    }
+    if let Some(file) = record.file() {
+      context.set("file", file);
+    }
+    if let Some(line) = record.line() {
+      context.set("line", line);
+    }
+
+    self.ctx(context).log_at(
+      level,
+      &record.args().to_string(),
+      record.file(),
+      record.line(),
+      record.module_path(),
+    );
+  }
+
+  fn flush(&self) {
+    let _ = Logger::flush(self);
+  }
+}
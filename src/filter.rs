@@ -0,0 +1,236 @@
+use std::env;
+use std::error;
+use std::fmt;
+use super::Context;
+use super::Level;
+
+/// Per-subsystem level filtering for a [`Logger`](struct.Logger.html).
+///
+/// A filter is built from a comma separated list of directives in the style
+/// of `RUST_LOG`, such as `info,db=trace,http::client=warn`. A directive of
+/// the form `name=level` applies to messages whose context value for the
+/// filter's key is `name`, or starts with `name::`. Messages without a value
+/// for the key are matched by the module path they were logged from, which
+/// the logging macros record. The longest matching name wins. A bare level
+/// applies to every other message. If no directive applies, the logger's
+/// own level is used. Levels are parsed by
+/// [`Level::from_str`](enum.Level.html#impl-FromStr-for-Level), so aliases
+/// such as `warning` are accepted.
+///
+/// The filter's key defaults to `src`. Records forwarded from the `log`
+/// crate or `tracing` carry their module path under the `module` key, so a
+/// filter keyed on `module` filters those by module path.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::Filter;
+///
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// logger.set_filter(Filter::parse("info,db=trace,http::client=warn").unwrap());
+///
+/// let db_logger = logger.ctx(context!{ src: "db" });
+/// let client_logger = logger.ctx(context!{ src: "http::client::pool" });
+///
+/// assert!(!logger.enabled(Debug, None));
+/// assert!(db_logger.enabled(Trace, None));
+/// assert!(!client_logger.enabled(Info, None));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+  key: String,
+  default: Option<Level>,
+  directives: Vec<(String, Level)>,
+}
+
+impl Filter {
+  /// Creates a new filter without any directives.
+  pub fn new() -> Self {
+    Self {
+      key: "src".to_string(),
+      default: None,
+      directives: Vec::new(),
+    }
+  }
+
+  /// Parses a filter from a comma separated list of directives.
+  ///
+  /// # Arguments
+  ///
+  /// * `directives` - Directives such as `info,db=trace`.
+  pub fn parse(directives: &str) -> Result<Self, ParseFilterError> {
+    let mut filter = Self::new();
+    for directive in directives.split(',').map(str::trim) {
+      if directive.is_empty() {
+        continue;
+      }
+      let mut parts = directive.splitn(2, '=');
+      let first = parts.next().unwrap_or("").trim();
+      match parts.next() {
+        Some(level) => {
+          if first.is_empty() {
+            return Err(ParseFilterError::new(directive, "missing name"));
+          }
+          filter = filter.directive(first, parse_level(directive, level)?);
+        }
+        None => filter.default = Some(parse_level(directive, first)?),
+      }
+    }
+    Ok(filter)
+  }
+
+  /// Parses a filter from an environment variable. If the variable is not
+  /// set an empty filter is returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - The name of the environment variable, such as `QUIL_LOG`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use quil::prelude::*;
+  /// # use quil::Filter;
+  /// std::env::set_var("QUIL_LOG", "warn,db=debug");
+  ///
+  /// let filter = Filter::from_env("QUIL_LOG").unwrap();
+  /// assert_eq!(filter.default_level(), Some(Warn));
+  /// assert_eq!(filter.max_level(), Some(Debug));
+  /// ```
+  pub fn from_env(name: &str) -> Result<Self, ParseFilterError> {
+    match env::var(name) {
+      Ok(directives) => Self::parse(&directives),
+      Err(_) => Ok(Self::new()),
+    }
+  }
+
+  /// Set the context key directive names are matched against.
+  pub fn key<S>(mut self, key: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.key = key.into();
+    self
+  }
+
+  /// Add a directive setting the level for messages from `name`.
+  pub fn directive<S>(mut self, name: S, level: Level) -> Self
+  where
+    S: Into<String>,
+  {
+    let name = name.into();
+    self.directives.retain(|directive| directive.0 != name);
+    self.directives.push((name, level));
+    self
+      .directives
+      .sort_by_key(|directive| ::std::cmp::Reverse(directive.0.len()));
+    self
+  }
+
+  /// Returns the level a message with the given context is filtered at, or
+  /// `None` if no directive applies.
+  ///
+  /// # Arguments
+  ///
+  /// * `context` - The context of the message.
+  pub fn level_for(&self, context: &Context) -> Option<Level> {
+    self.level_for_message(context, None)
+  }
+
+  /// Returns the level a message with the given context, logged from the
+  /// given module, is filtered at, or `None` if no directive applies. The
+  /// module path is only matched if the context has no value for the
+  /// filter's key.
+  ///
+  /// # Arguments
+  ///
+  /// * `context` - The context of the message.
+  /// * `module` - The module path the message was logged from.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # #[macro_use] extern crate quil;
+  /// # fn main() {
+  /// # use quil::prelude::*;
+  /// use quil::Filter;
+  ///
+  /// let filter = Filter::parse("warn,app::db=trace").unwrap();
+  ///
+  /// assert_eq!(filter.level_for_message(&context!{}, Some("app::db::pool")), Some(Trace));
+  /// assert_eq!(filter.level_for_message(&context!{ src: "http" }, Some("app::db")), Some(Warn));
+  /// # }
+  /// ```
+  pub fn level_for_message(&self, context: &Context, module: Option<&str>) -> Option<Level> {
+    let value = match context.get(&self.key) {
+      Some(value) => value.as_str(),
+      None => module,
+    };
+    if let Some(value) = value {
+      for &(ref name, level) in &self.directives {
+        let matches = value == name
+          || (value.starts_with(name.as_str()) && value[name.len()..].starts_with("::"));
+        if matches {
+          return Some(level);
+        }
+      }
+    }
+    self.default
+  }
+
+  /// Returns the level of the bare directive applying to messages no named
+  /// directive matches, if there is one.
+  pub fn default_level(&self) -> Option<Level> {
+    self.default
+  }
+
+  /// Returns the most verbose level any directive allows.
+  pub fn max_level(&self) -> Option<Level> {
+    self
+      .directives
+      .iter()
+      .map(|directive| directive.1)
+      .chain(self.default)
+      .max()
+  }
+}
+
+impl Default for Filter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// The error returned when a filter directive cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFilterError {
+  directive: String,
+  reason: String,
+}
+
+impl ParseFilterError {
+  fn new(directive: &str, reason: &str) -> Self {
+    Self {
+      directive: directive.to_string(),
+      reason: reason.to_string(),
+    }
+  }
+}
+
+impl fmt::Display for ParseFilterError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "invalid filter directive \"{}\": {}", self.directive, self.reason)
+  }
+}
+
+impl error::Error for ParseFilterError {}
+
+fn parse_level(directive: &str, level: &str) -> Result<Level, ParseFilterError> {
+  level
+    .parse()
+    .map_err(|_| ParseFilterError::new(directive, "unknown level"))
+}
@@ -0,0 +1,29 @@
+use super::super::Record;
+
+/// Structs that implement `Formatter` can be used with targets that write
+/// lines of text such as [`RollingFile`](../targets/struct.RollingFile.html).
+///
+/// # Examples
+///
+/// An example of a basic formatter:
+///
+/// ```rust
+/// # use quil::Record;
+/// # use quil::formatters::Formatter;
+/// struct Simple;
+///
+/// impl Formatter for Simple {
+///   fn format(&self, record: &Record) -> String {
+///     format!("{} {} {}", record.level(), record.message(), record.context())
+///   }
+/// }
+/// ```
+pub trait Formatter {
+  /// Formats a message as a single line. The returned line should not
+  /// contain a trailing newline.
+  ///
+  /// # Arguments
+  ///
+  /// * `record` - The record of the message to format.
+  fn format(&self, record: &Record) -> String;
+}
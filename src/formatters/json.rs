@@ -0,0 +1,209 @@
+use std::fmt::Write;
+use std::process;
+use hostname;
+use super::super::Record;
+use super::super::Value;
+use super::Formatter;
+use super::timestamp::{self, Precision, Timezone};
+
+/// Formats messages as JSON objects, one per line. This is the format
+/// written by [`JsonFile`](../targets/struct.JsonFile.html).
+///
+/// Each object carries an RFC 3339 `timestamp` in UTC with millisecond
+/// precision by default. The precision and timezone can be changed, and the
+/// `hostname`, `pid` and `thread` fields can be added.
+///
+/// Context values are written in their native JSON form, so numbers and
+/// booleans are not quoted. Every line is valid JSON whatever the message
+/// and context contain. Strings are escaped with
+/// [`escape_json`](fn.escape_json.html) and non-finite floats are written
+/// as `null`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::Record;
+/// # use quil::formatters::{Formatter, Json};
+/// let json = Json::new().show_timestamp(false);
+/// let context = context!{ retries: 3 };
+/// let line = json.format(&Record::new(Info, "hello", &context));
+///
+/// assert_eq!(
+///   line,
+///   "{ \"level\": \"info\", \"message\": \"hello\", \"context\": { \"retries\": 3 } }"
+/// );
+/// # }
+/// ```
+pub struct Json {
+  show_timestamp: bool,
+  precision: Precision,
+  timezone: Timezone,
+  hostname: Option<String>,
+  show_pid: bool,
+  show_thread: bool,
+}
+
+impl Json {
+  /// Creates a new `Json` formatter which writes UTC timestamps with
+  /// millisecond precision.
+  pub fn new() -> Self {
+    Self {
+      show_timestamp: true,
+      precision: Precision::Millis,
+      timezone: Timezone::Utc,
+      hostname: None,
+      show_pid: false,
+      show_thread: false,
+    }
+  }
+
+  /// Enable/disable timestamps.
+  pub fn show_timestamp(mut self, show_timestamp: bool) -> Self {
+    self.show_timestamp = show_timestamp;
+    self
+  }
+
+  /// Set the number of fractional second digits in timestamps.
+  pub fn precision(mut self, precision: Precision) -> Self {
+    self.precision = precision;
+    self
+  }
+
+  /// Set the timezone timestamps are written in.
+  pub fn timezone(mut self, timezone: Timezone) -> Self {
+    self.timezone = timezone;
+    self
+  }
+
+  /// Enable/disable the `hostname` field. The hostname is looked up once,
+  /// when this is enabled.
+  pub fn show_hostname(mut self, show_hostname: bool) -> Self {
+    self.hostname = if show_hostname {
+      hostname::get()
+        .map(|hostname| hostname.to_string_lossy().into_owned())
+        .ok()
+    } else {
+      None
+    };
+    self
+  }
+
+  /// Enable/disable the `pid` field.
+  pub fn show_pid(mut self, show_pid: bool) -> Self {
+    self.show_pid = show_pid;
+    self
+  }
+
+  /// Enable/disable the `thread` field. This is the name of the thread
+  /// the message was logged from, or its id if it has no name.
+  pub fn show_thread(mut self, show_thread: bool) -> Self {
+    self.show_thread = show_thread;
+    self
+  }
+}
+
+impl Default for Json {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Formatter for Json {
+  fn format(&self, record: &Record) -> String {
+    let mut line = String::from("{ ");
+    if self.show_timestamp {
+      let timestamp = timestamp::rfc3339(&record.timestamp(), self.precision, self.timezone);
+      let _ = write!(line, "\"timestamp\": \"{}\", ", timestamp);
+    }
+    let _ = write!(
+      line,
+      "\"level\": \"{}\", \"message\": \"{}\", ",
+      record.level(),
+      escape_json(record.message())
+    );
+    if let Some(ref hostname) = self.hostname {
+      let _ = write!(line, "\"hostname\": \"{}\", ", escape_json(hostname));
+    }
+    if self.show_pid {
+      let _ = write!(line, "\"pid\": {}, ", process::id());
+    }
+    if self.show_thread {
+      let name = match record.thread_name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", record.thread_id()),
+      };
+      let _ = write!(line, "\"thread\": \"{}\", ", escape_json(&name));
+    }
+
+    let context = record
+      .context()
+      .iter()
+      .map(|(key, val)| format!("\"{}\": {}", escape_json(key), json_value(val)))
+      .collect::<Vec<String>>()
+      .join(", ");
+    let _ = write!(line, "\"context\": {{ {} }} }}", context);
+    line
+  }
+}
+
+fn json_value(value: &Value) -> String {
+  match *value {
+    Value::String(ref value) => format!("\"{}\"", escape_json(value)),
+    Value::F64(value) if !value.is_finite() => "null".to_string(),
+    Value::Null => "null".to_string(),
+    Value::Array(ref values) => {
+      let values: Vec<_> = values.iter().map(json_value).collect();
+      format!("[{}]", values.join(", "))
+    }
+    Value::Map(ref values) => {
+      let values: Vec<_> = values
+        .iter()
+        .map(|(key, val)| format!("\"{}\": {}", escape_json(key), json_value(val)))
+        .collect();
+      format!("{{ {} }}", values.join(", "))
+    }
+    ref value => value.to_string(),
+  }
+}
+
+/// Escapes a string for use within a JSON string literal.
+///
+/// Quotes and backslashes are escaped, as are all control characters.
+/// `\n`, `\r`, `\t`, `\b` and `\f` use their short forms and every other
+/// control character, along with `U+007F`, `U+2028` and `U+2029`, is
+/// written as a `\uXXXX` escape. The surrounding quotes are not added.
+///
+/// Rust strings are always valid UTF-8, so text from lossy sources should be
+/// converted first. [`Value::from_utf8_lossy`](../enum.Value.html#method.from_utf8_lossy)
+/// and [`Value::from_utf16_lossy`](../enum.Value.html#method.from_utf16_lossy)
+/// replace invalid sequences and lone surrogates with `U+FFFD`.
+///
+/// # Examples
+///
+/// ```rust
+/// use quil::formatters::escape_json;
+///
+/// assert_eq!(escape_json("a \"quote\"\n\u{1}"), "a \\\"quote\\\"\\n\\u0001");
+/// ```
+pub fn escape_json(s: &str) -> String {
+  let mut escaped = String::with_capacity(s.len() + 2);
+  for c in s.chars() {
+    match c {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      '\u{8}' => escaped.push_str("\\b"),
+      '\u{c}' => escaped.push_str("\\f"),
+      '\u{0}'..='\u{1f}' | '\u{7f}' | '\u{2028}' | '\u{2029}' => {
+        let _ = write!(escaped, "\\u{:04x}", c as u32);
+      }
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write;
+use super::super::Record;
+use super::super::Value;
+use super::Formatter;
+use super::timestamp::{self, Precision, Timezone};
+
+/// Formats messages as logfmt lines, such as
+/// `ts=2017-11-07T23:55:42.118Z level=info msg="user logged in" id=42`.
+///
+/// The timestamp, level and message come first, followed by the context
+/// sorted by key, so lines for the same record are always identical.
+/// Values containing spaces, `=`, quotes or control characters are quoted
+/// and escaped, and empty values are written as `""`. Characters which are
+/// not allowed in keys are replaced with `_`. Context keys named `ts`,
+/// `level` or `msg` are written with a `ctx.` prefix, such as `ctx.level`,
+/// so that each key appears once in a line.
+///
+/// Timestamps are RFC 3339 in UTC with millisecond precision by default.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::Record;
+/// use quil::formatters::{Formatter, Logfmt};
+///
+/// let logfmt = Logfmt::new().show_timestamp(false);
+/// let context = context!{ user: "Jane Doe", id: 42 };
+/// let line = logfmt.format(&Record::new(Info, "logged in", &context));
+///
+/// assert_eq!(line, "level=info msg=\"logged in\" id=42 user=\"Jane Doe\"");
+/// # }
+/// ```
+pub struct Logfmt {
+  show_timestamp: bool,
+  precision: Precision,
+  timezone: Timezone,
+}
+
+impl Logfmt {
+  /// Creates a new `Logfmt` formatter which writes UTC timestamps with
+  /// millisecond precision.
+  pub fn new() -> Self {
+    Self {
+      show_timestamp: true,
+      precision: Precision::Millis,
+      timezone: Timezone::Utc,
+    }
+  }
+
+  /// Enable/disable the `ts` field.
+  pub fn show_timestamp(mut self, show_timestamp: bool) -> Self {
+    self.show_timestamp = show_timestamp;
+    self
+  }
+
+  /// Set the number of fractional second digits in timestamps.
+  pub fn precision(mut self, precision: Precision) -> Self {
+    self.precision = precision;
+    self
+  }
+
+  /// Set the timezone timestamps are written in.
+  pub fn timezone(mut self, timezone: Timezone) -> Self {
+    self.timezone = timezone;
+    self
+  }
+}
+
+impl Default for Logfmt {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Formatter for Logfmt {
+  fn format(&self, record: &Record) -> String {
+    let mut line = String::new();
+    if self.show_timestamp {
+      let timestamp = timestamp::rfc3339(&record.timestamp(), self.precision, self.timezone);
+      let _ = write!(line, "ts={} ", timestamp);
+    }
+    let _ = write!(
+      line,
+      "level={} msg={}",
+      record.level(),
+      logfmt_value(record.message())
+    );
+
+    let mut pairs: Vec<_> = record.context().iter().collect();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    for (key, val) in pairs {
+      let val = match *val {
+        Value::String(ref val) => logfmt_value(val),
+        ref val => logfmt_value(&val.to_string()),
+      };
+      let key = logfmt_key(key);
+      let prefix = if RESERVED_KEYS.contains(&key.as_str()) { "ctx." } else { "" };
+      let _ = write!(line, " {}{}={}", prefix, key, val);
+    }
+    line
+  }
+}
+
+// The keys of the fields written before the context.
+const RESERVED_KEYS: [&str; 3] = ["ts", "level", "msg"];
+
+fn logfmt_key(key: &str) -> String {
+  if key.is_empty() {
+    return "_".to_string();
+  }
+  key
+    .chars()
+    .map(|c| if needs_quotes(c) || c == '\\' { '_' } else { c })
+    .collect()
+}
+
+fn logfmt_value(value: &str) -> String {
+  if !value.is_empty() && !value.chars().any(needs_quotes) {
+    return value.to_string();
+  }
+  let mut quoted = String::with_capacity(value.len() + 2);
+  quoted.push('"');
+  for c in value.chars() {
+    match c {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      '\r' => quoted.push_str("\\r"),
+      '\t' => quoted.push_str("\\t"),
+      c if c.is_control() => {
+        let _ = write!(quoted, "\\u{:04x}", c as u32);
+      }
+      c => quoted.push(c),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+fn needs_quotes(c: char) -> bool {
+  c <= ' ' || c == '=' || c == '"' || c.is_control() || c == '\u{fffd}'
+}
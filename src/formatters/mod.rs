@@ -0,0 +1,21 @@
+//! Contains line formatters and the formatter trait for implementing custom
+//! formatters. Formatters turn a logged message into a single line of text
+//! and can be shared by any target that writes text.
+
+mod formatter;
+mod json;
+mod logfmt;
+pub(crate) mod style;
+mod template;
+mod text;
+mod theme;
+mod timestamp;
+
+pub use self::formatter::Formatter;
+pub use self::json::{escape_json, Json};
+pub use self::logfmt::Logfmt;
+pub use self::style::{Color, ParseStyleError, Style};
+pub use self::template::{ParseTemplateError, Template};
+pub use self::text::Text;
+pub use self::theme::{ParseThemeError, Theme};
+pub use self::timestamp::{Precision, Timezone};
@@ -0,0 +1,270 @@
+use std::error;
+use std::fmt;
+
+/// The ANSI terminal colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+  Black,
+  Red,
+  Green,
+  Yellow,
+  Blue,
+  Magenta,
+  Cyan,
+  White,
+  BrightBlack,
+  BrightRed,
+  BrightGreen,
+  BrightYellow,
+  BrightBlue,
+  BrightMagenta,
+  BrightCyan,
+  BrightWhite,
+}
+
+impl Color {
+  /// Parses a colour name such as `red` or `bright_red`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use quil::formatters::Color;
+  ///
+  /// assert_eq!(Color::parse("bright_red"), Some(Color::BrightRed));
+  /// assert_eq!(Color::parse("sparkly"), None);
+  /// ```
+  pub fn parse(name: &str) -> Option<Self> {
+    match name.to_lowercase().replace(' ', "_").as_str() {
+      "black" => Some(Color::Black),
+      "red" => Some(Color::Red),
+      "green" => Some(Color::Green),
+      "yellow" => Some(Color::Yellow),
+      "blue" => Some(Color::Blue),
+      "magenta" | "purple" => Some(Color::Magenta),
+      "cyan" => Some(Color::Cyan),
+      "white" => Some(Color::White),
+      "bright_black" | "gray" | "grey" => Some(Color::BrightBlack),
+      "bright_red" => Some(Color::BrightRed),
+      "bright_green" => Some(Color::BrightGreen),
+      "bright_yellow" => Some(Color::BrightYellow),
+      "bright_blue" => Some(Color::BrightBlue),
+      "bright_magenta" => Some(Color::BrightMagenta),
+      "bright_cyan" => Some(Color::BrightCyan),
+      "bright_white" => Some(Color::BrightWhite),
+      _ => None,
+    }
+  }
+
+  fn code(self) -> u8 {
+    match self {
+      Color::Black => 30,
+      Color::Red => 31,
+      Color::Green => 32,
+      Color::Yellow => 33,
+      Color::Blue => 34,
+      Color::Magenta => 35,
+      Color::Cyan => 36,
+      Color::White => 37,
+      Color::BrightBlack => 90,
+      Color::BrightRed => 91,
+      Color::BrightGreen => 92,
+      Color::BrightYellow => 93,
+      Color::BrightBlue => 94,
+      Color::BrightMagenta => 95,
+      Color::BrightCyan => 96,
+      Color::BrightWhite => 97,
+    }
+  }
+}
+
+/// A foreground colour, background colour and text attributes, written as
+/// an ANSI SGR escape sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// use quil::formatters::{Color, Style};
+///
+/// let style = Style::new().fg(Color::Red).bold();
+///
+/// assert_eq!(style, Style::parse("bold red").unwrap());
+/// assert_eq!(style.paint("error"), "\u{1b}[1;31merror\u{1b}[0m");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+  pub(crate) fg: Option<Color>,
+  pub(crate) bg: Option<Color>,
+  pub(crate) bold: bool,
+  pub(crate) dimmed: bool,
+  pub(crate) italic: bool,
+  pub(crate) underline: bool,
+}
+
+impl Style {
+  /// Creates a new `Style` which leaves text unchanged.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Parses a style from a space separated list of words. Words are colour
+  /// names such as `red` or `bright_red` for the foreground, colour names
+  /// prefixed with `on_` for the background, and `bold`, `dimmed`, `italic`
+  /// and `underline`. `none` and an empty string leave text unchanged.
+  ///
+  /// # Arguments
+  ///
+  /// * `style` - The style, such as `"bold red on_white"`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use quil::formatters::Style;
+  ///
+  /// let error = Style::parse("bold on_sparkly").unwrap_err();
+  /// assert_eq!(error.word(), "sparkly");
+  /// assert_eq!(error.to_string(), "unknown colour `sparkly`");
+  /// ```
+  pub fn parse(style: &str) -> Result<Self, ParseStyleError> {
+    let mut parsed = Self::new();
+    for word in style.split_whitespace() {
+      match word {
+        "none" => {}
+        "bold" => parsed.bold = true,
+        "dimmed" => parsed.dimmed = true,
+        "italic" => parsed.italic = true,
+        "underline" => parsed.underline = true,
+        word if word.starts_with("on_") => parsed.bg = Some(parse_color(&word[3..])?),
+        word => parsed.fg = Some(parse_color(word)?),
+      }
+    }
+    Ok(parsed)
+  }
+
+  /// Set the foreground colour.
+  pub fn fg(mut self, color: Color) -> Self {
+    self.fg = Some(color);
+    self
+  }
+
+  /// Set the background colour.
+  pub fn bg(mut self, color: Color) -> Self {
+    self.bg = Some(color);
+    self
+  }
+
+  /// Make text bold.
+  pub fn bold(mut self) -> Self {
+    self.bold = true;
+    self
+  }
+
+  /// Make text dimmed.
+  pub fn dimmed(mut self) -> Self {
+    self.dimmed = true;
+    self
+  }
+
+  /// Make text italic.
+  pub fn italic(mut self) -> Self {
+    self.italic = true;
+    self
+  }
+
+  /// Underline text.
+  pub fn underline(mut self) -> Self {
+    self.underline = true;
+    self
+  }
+
+  /// Returns a style with the colours and attributes of `self`, using those
+  /// of `other` where `self` has none.
+  pub(crate) fn or(self, other: Style) -> Style {
+    Style {
+      fg: self.fg.or(other.fg),
+      bg: self.bg.or(other.bg),
+      bold: self.bold || other.bold,
+      dimmed: self.dimmed || other.dimmed,
+      italic: self.italic || other.italic,
+      underline: self.underline || other.underline,
+    }
+  }
+
+  /// Returns `text` wrapped in the escape sequences for this style, or
+  /// `text` unchanged if the style is empty.
+  pub fn paint(&self, text: &str) -> String {
+    let mut codes = Vec::new();
+    if self.bold {
+      codes.push(1);
+    }
+    if self.dimmed {
+      codes.push(2);
+    }
+    if self.italic {
+      codes.push(3);
+    }
+    if self.underline {
+      codes.push(4);
+    }
+    if let Some(fg) = self.fg {
+      codes.push(fg.code());
+    }
+    if let Some(bg) = self.bg {
+      codes.push(bg.code() + 10);
+    }
+    if codes.is_empty() {
+      return text.to_string();
+    }
+    let codes: Vec<_> = codes.iter().map(u8::to_string).collect();
+    format!("\u{1b}[{}m{}\u{1b}[0m", codes.join(";"), text)
+  }
+}
+
+/// The error returned when a style contains a word which is not a colour or
+/// attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStyleError {
+  word: String,
+}
+
+impl ParseStyleError {
+  /// Returns the colour name which was not recognised, without any `on_`
+  /// prefix.
+  pub fn word(&self) -> &str {
+    &self.word
+  }
+}
+
+impl fmt::Display for ParseStyleError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "unknown colour `{}`", self.word)
+  }
+}
+
+impl error::Error for ParseStyleError {}
+
+fn parse_color(name: &str) -> Result<Color, ParseStyleError> {
+  Color::parse(name).ok_or_else(|| ParseStyleError {
+    word: name.to_string(),
+  })
+}
+
+/// Removes ANSI escape sequences from `text`.
+pub(crate) fn strip(text: &str) -> String {
+  let mut stripped = String::with_capacity(text.len());
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '\u{1b}' {
+      stripped.push(c);
+      continue;
+    }
+    if chars.peek() == Some(&'[') {
+      chars.next();
+      for c in chars.by_ref() {
+        if ('\u{40}'..='\u{7e}').contains(&c) {
+          break;
+        }
+      }
+    }
+  }
+  stripped
+}
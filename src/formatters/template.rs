@@ -0,0 +1,453 @@
+use std::error;
+use std::fmt;
+use chrono::Local;
+use chrono::format::{Item, StrftimeItems};
+use super::super::Level;
+use super::super::Record;
+use super::{Formatter, ParseStyleError, Style, Theme, Timezone};
+
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+
+/// Formats messages using a template string. The template is parsed once
+/// when the `Template` is created.
+///
+/// Text outside of braces is copied as is, and `{{` and `}}` write a
+/// literal brace. Each `{field}` is replaced by a part of the record:
+///
+/// * `{time}` - The time the message was logged. A `strftime` format may
+///   follow a colon, as in `{time:%H:%M:%S%.3f}`.
+/// * `{level}` - The level of the message.
+/// * `{message}` - The message.
+/// * `{ctx.key}` - The context value for `key`, or nothing if it is not set.
+/// * `{ctx}` - Every context value, as space separated `key=value` pairs
+///   sorted by key.
+/// * `{ctx:rest}` - As `{ctx}`, but leaving out keys the template shows
+///   with `{ctx.key}`.
+/// * `{thread}` - The name of the thread the message was logged from, or
+///   its id if it has no name.
+/// * `{file}`, `{line}` and `{module}` - Where the message was logged from,
+///   if known.
+///
+/// Fields other than `time` and `ctx` accept a format spec after a colon:
+/// an optional alignment of `<`, `>` or `^`, a minimum width, and a maximum
+/// width after a `.`. For example `{level:>7}` right aligns the level and
+/// `{ctx.src:<12.12}` pads or truncates the value to 12 characters.
+///
+/// A field can be coloured by adding `|` and a list of styles, such as
+/// `{ctx.src|bold cyan}`. The first single `|` starts the styles, so a `|`
+/// in a time format is written as `||`, as in `{time:%H:%M||%S|dimmed}`.
+/// Styles are the colour names `black`, `red`, `green`, `yellow`, `blue`,
+/// `magenta`, `cyan` and `white`, their `bright_` variants, background
+/// colours written `on_red`, and `bold`, `dimmed`, `italic` and
+/// `underline`. The style `level` colours the field by the message's level,
+/// using the template's [`Theme`](struct.Theme.html).
+/// Styles are only applied if colour is enabled with
+/// [`colored`](#method.colored).
+///
+/// Trailing whitespace is removed from each line, so a template ending in
+/// `{ctx:rest}` does not leave a trailing space when there is no context.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::Record;
+/// use quil::formatters::{Formatter, Template};
+///
+/// let template = Template::parse("[{level:>7|level}] {ctx.src:.4}: {message} {ctx:rest}").unwrap();
+///
+/// let context = context!{ src: "http::client", retries: 3 };
+/// let line = template.format(&Record::new(Warn, "timed out", &context));
+///
+/// assert_eq!(line, "[   warn] http: timed out retries=3");
+/// # }
+/// ```
+///
+/// Any target that accepts a formatter can use a template:
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::formatters::Template;
+/// use quil::targets::ConsoleOptions;
+///
+/// let template = Template::parse("{time:%H:%M:%S%.3f} [{level:>7}] {ctx.src}: {message} {ctx:rest}")
+///   .unwrap()
+///   .colored(true);
+/// let logger = Logger::new(ConsoleOptions::new().formatter(template).build(), context!{ src: "root" });
+///
+/// logger.info("hello");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Template {
+  segments: Vec<Segment>,
+  context_keys: Vec<String>,
+  timezone: Timezone,
+  colored: bool,
+  theme: Theme,
+}
+
+impl Template {
+  /// Parses a template.
+  ///
+  /// # Arguments
+  ///
+  /// * `template` - The template, such as `"{level}: {message}"`.
+  pub fn parse(template: &str) -> Result<Self, ParseTemplateError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+      match c {
+        '{' if chars.peek().map(|&(_, c)| c) == Some('{') => {
+          chars.next();
+          literal.push('{');
+        }
+        '}' if chars.peek().map(|&(_, c)| c) == Some('}') => {
+          chars.next();
+          literal.push('}');
+        }
+        '}' => return Err(ParseTemplateError::new(position, "unmatched `}`")),
+        '{' => {
+          let start = position + 1;
+          let end = loop {
+            match chars.next() {
+              Some((end, '}')) => break end,
+              Some((_, '{')) | None => {
+                return Err(ParseTemplateError::new(position, "unclosed `{`"));
+              }
+              Some(_) => {}
+            }
+          };
+          if !literal.is_empty() {
+            segments.push(Segment::Literal(literal.split_off(0)));
+          }
+          segments.push(parse_field(&template[start..end], position)?);
+        }
+        c => literal.push(c),
+      }
+    }
+    if !literal.is_empty() {
+      segments.push(Segment::Literal(literal));
+    }
+
+    let context_keys = segments
+      .iter()
+      .filter_map(|segment| match *segment {
+        Segment::Field(Field::Context(ref key), _, _) => Some(key.clone()),
+        _ => None,
+      })
+      .collect();
+
+    Ok(Self {
+      segments,
+      context_keys,
+      timezone: Timezone::Local,
+      colored: false,
+      theme: Theme::dark(),
+    })
+  }
+
+  /// Set the timezone `{time}` is written in. Defaults to local time.
+  pub fn timezone(mut self, timezone: Timezone) -> Self {
+    self.timezone = timezone;
+    self
+  }
+
+  /// Enable/disable colour styles. Disabled by default.
+  pub fn colored(mut self, colored: bool) -> Self {
+    self.colored = colored;
+    self
+  }
+
+  /// Set the theme the `level` style takes its colours from. Defaults to
+  /// [`Theme::dark`](struct.Theme.html#method.dark).
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.theme = theme;
+    self
+  }
+
+  fn context_pairs(&self, record: &Record, rest: bool) -> String {
+    let mut pairs: Vec<_> = record
+      .context()
+      .iter()
+      .filter(|&(key, _)| !rest || !self.context_keys.contains(key))
+      .collect();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    pairs
+      .into_iter()
+      .map(|(key, val)| format!("{}={}", key, val))
+      .collect::<Vec<String>>()
+      .join(" ")
+  }
+}
+
+impl Formatter for Template {
+  fn format(&self, record: &Record) -> String {
+    let mut line = String::new();
+    for segment in &self.segments {
+      let (field, spec, style) = match *segment {
+        Segment::Literal(ref literal) => {
+          line += literal;
+          continue;
+        }
+        Segment::Field(ref field, ref spec, ref style) => (field, spec, style),
+      };
+
+      let value = match *field {
+        Field::Time(ref format) => match self.timezone {
+          Timezone::Utc => record.timestamp().format(format).to_string(),
+          Timezone::Local => record
+            .timestamp()
+            .with_timezone(&Local)
+            .format(format)
+            .to_string(),
+        },
+        Field::Level => record.level().to_string(),
+        Field::Message => record.message().to_string(),
+        Field::Context(ref key) => record
+          .context()
+          .get(key)
+          .map(|value| value.to_string())
+          .unwrap_or_default(),
+        Field::ContextAll => self.context_pairs(record, false),
+        Field::ContextRest => self.context_pairs(record, true),
+        Field::Thread => match record.thread_name() {
+          Some(name) => name.to_string(),
+          None => format!("{:?}", record.thread_id()),
+        },
+        Field::File => record.file().unwrap_or("").to_string(),
+        Field::Line => record.line().map(|line| line.to_string()).unwrap_or_default(),
+        Field::Module => record.module().unwrap_or("").to_string(),
+      };
+
+      let value = spec.apply(value);
+      match *style {
+        Some(ref style) if self.colored => {
+          line += &style.paint(&value, &self.theme, record.level())
+        }
+        _ => line += &value,
+      }
+    }
+    let len = line.trim_end().len();
+    line.truncate(len);
+    line
+  }
+}
+
+/// The error returned when a template cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTemplateError {
+  position: usize,
+  reason: String,
+}
+
+impl ParseTemplateError {
+  fn new<S>(position: usize, reason: S) -> Self
+  where
+    S: Into<String>,
+  {
+    Self {
+      position,
+      reason: reason.into(),
+    }
+  }
+
+  /// Returns the byte offset in the template of the field which could not
+  /// be parsed.
+  pub fn position(&self) -> usize {
+    self.position
+  }
+}
+
+impl fmt::Display for ParseTemplateError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "invalid template at {}: {}", self.position, self.reason)
+  }
+}
+
+impl error::Error for ParseTemplateError {}
+
+#[derive(Debug, Clone)]
+enum Segment {
+  Literal(String),
+  Field(Field, Spec, Option<FieldStyle>),
+}
+
+#[derive(Debug, Clone)]
+enum Field {
+  Time(String),
+  Level,
+  Message,
+  Context(String),
+  ContextAll,
+  ContextRest,
+  Thread,
+  File,
+  Line,
+  Module,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+  Left,
+  Right,
+  Center,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Spec {
+  align: Align,
+  width: usize,
+  max: Option<usize>,
+}
+
+impl Spec {
+  fn parse(spec: &str) -> Option<Self> {
+    let (align, spec) = match spec.chars().next() {
+      Some('<') => (Align::Left, &spec[1..]),
+      Some('>') => (Align::Right, &spec[1..]),
+      Some('^') => (Align::Center, &spec[1..]),
+      _ => (Align::Left, spec),
+    };
+    let (width, max) = match spec.find('.') {
+      Some(dot) => (&spec[..dot], Some(&spec[dot + 1..])),
+      None => (spec, None),
+    };
+    let width = if width.is_empty() {
+      0
+    } else {
+      width.parse().ok()?
+    };
+    let max = match max {
+      Some(max) => Some(max.parse().ok()?),
+      None => None,
+    };
+    Some(Self { align, width, max })
+  }
+
+  fn apply(&self, mut value: String) -> String {
+    if let Some(max) = self.max {
+      if let Some((index, _)) = value.char_indices().nth(max) {
+        value.truncate(index);
+      }
+    }
+    let len = value.chars().count();
+    if len >= self.width {
+      return value;
+    }
+    let pad = self.width - len;
+    let (left, right) = match self.align {
+      Align::Left => (0, pad),
+      Align::Right => (pad, 0),
+      Align::Center => (pad / 2, pad - pad / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), value, " ".repeat(right))
+  }
+}
+
+impl Default for Spec {
+  fn default() -> Self {
+    Self {
+      align: Align::Left,
+      width: 0,
+      max: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldStyle {
+  level: bool,
+  style: Style,
+}
+
+impl FieldStyle {
+  fn parse(style: &str) -> Result<Self, ParseStyleError> {
+    let words: Vec<_> = style.split_whitespace().collect();
+    let level = words.contains(&"level");
+    let words: Vec<_> = words.into_iter().filter(|&word| word != "level").collect();
+    Ok(Self {
+      level,
+      style: Style::parse(&words.join(" "))?,
+    })
+  }
+
+  fn paint(&self, value: &str, theme: &Theme, level: Level) -> String {
+    if self.level {
+      self.style.or(theme.level_style(level)).paint(value)
+    } else {
+      self.style.paint(value)
+    }
+  }
+}
+
+// Splits a field at its first single `|`, unescaping each `||` before it.
+fn split_style(field: &str) -> (String, Option<&str>) {
+  let mut unescaped = String::with_capacity(field.len());
+  let mut chars = field.char_indices().peekable();
+  while let Some((index, c)) = chars.next() {
+    if c == '|' {
+      match chars.peek() {
+        Some(&(_, '|')) => {
+          chars.next();
+        }
+        _ => return (unescaped, Some(&field[index + 1..])),
+      }
+    }
+    unescaped.push(c);
+  }
+  (unescaped, None)
+}
+
+fn parse_field(field: &str, position: usize) -> Result<Segment, ParseTemplateError> {
+  let error = |reason: String| ParseTemplateError::new(position, reason);
+
+  let (field, style) = split_style(field);
+  let style = match style {
+    Some(style) => {
+      Some(FieldStyle::parse(style).map_err(|style_error| error(style_error.to_string()))?)
+    }
+    None => None,
+  };
+  let (name, spec) = match field.find(':') {
+    Some(colon) => (field[..colon].trim(), Some(&field[colon + 1..])),
+    None => (field.trim(), None),
+  };
+
+  let field = match name {
+    "time" => {
+      let format = spec.unwrap_or(DEFAULT_TIME_FORMAT);
+      if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(error(format!("invalid time format `{}`", format)));
+      }
+      return Ok(Segment::Field(Field::Time(format.to_string()), Spec::default(), style));
+    }
+    "ctx" => {
+      let field = match spec {
+        None => Field::ContextAll,
+        Some("rest") => Field::ContextRest,
+        Some(spec) => return Err(error(format!("unknown context spec `{}`", spec))),
+      };
+      return Ok(Segment::Field(field, Spec::default(), style));
+    }
+    "level" => Field::Level,
+    "message" => Field::Message,
+    "thread" => Field::Thread,
+    "file" => Field::File,
+    "line" => Field::Line,
+    "module" => Field::Module,
+    name if name.starts_with("ctx.") && name.len() > 4 => Field::Context(name[4..].to_string()),
+    name => return Err(error(format!("unknown field `{}`", name))),
+  };
+  let spec = match spec {
+    Some(spec) => Spec::parse(spec).ok_or_else(|| error(format!("invalid format spec `{}`", spec)))?,
+    None => Spec::default(),
+  };
+  Ok(Segment::Field(field, spec, style))
+}
@@ -0,0 +1,139 @@
+use chrono::Local;
+use super::super::Record;
+use super::{Formatter, Style, Theme};
+
+/// Formats messages as human readable lines. This is the format written by
+/// [`Console`](../targets/struct.Console.html).
+///
+/// Timestamps, levels and contexts are all shown by default and colour is
+/// disabled by default, which makes `Text` suitable for writing to files.
+/// When colour is enabled, each part of the line is styled by a
+/// [`Theme`](struct.Theme.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::Record;
+/// # use quil::formatters::{Formatter, Text};
+/// let text = Text::new().show_timestamp(false);
+/// let context = context!{ src: "root" };
+/// let line = text.format(&Record::new(Info, "hello", &context));
+///
+/// assert_eq!(line, "info:    hello src=root");
+/// # }
+/// ```
+pub struct Text {
+  show_timestamp: bool,
+  show_level: bool,
+  show_context: bool,
+  colored: bool,
+  theme: Theme,
+}
+
+impl Text {
+  /// Creates a new `Text` formatter which shows timestamps, levels and
+  /// contexts without colour.
+  pub fn new() -> Self {
+    Self {
+      show_timestamp: true,
+      show_level: true,
+      show_context: true,
+      colored: false,
+      theme: Theme::dark(),
+    }
+  }
+
+  /// Enable/disable timestamps.
+  pub fn show_timestamp(mut self, show_timestamp: bool) -> Self {
+    self.show_timestamp = show_timestamp;
+    self
+  }
+
+  /// Enable/disable log levels.
+  pub fn show_level(mut self, show_level: bool) -> Self {
+    self.show_level = show_level;
+    self
+  }
+
+  /// Enable/disable log message contexts.
+  pub fn show_context(mut self, show_context: bool) -> Self {
+    self.show_context = show_context;
+    self
+  }
+
+  /// Enable/disable colour.
+  pub fn colored(mut self, colored: bool) -> Self {
+    self.colored = colored;
+    self
+  }
+
+  /// Set the theme used when colour is enabled. Defaults to
+  /// [`Theme::dark`](struct.Theme.html#method.dark).
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.theme = theme;
+    self
+  }
+
+  fn paint(&self, style: Style, text: &str) -> String {
+    if self.colored {
+      style.paint(text)
+    } else {
+      text.to_string()
+    }
+  }
+}
+
+impl Default for Text {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Formatter for Text {
+  fn format(&self, record: &Record) -> String {
+    let level = record.level();
+    let mut message = record.message().to_string();
+
+    let mut context_pairs: Vec<_> = record.context().iter().collect();
+    context_pairs.sort_by(|a, b| a.0.cmp(b.0));
+
+    if self.show_context {
+      message += " ";
+      message += &context_pairs
+        .into_iter()
+        .map(|(key, val)| {
+          format!(
+            "{}={}",
+            self.paint(self.theme.key_style(), key),
+            self.paint(self.theme.value_style(), &val.to_string())
+          )
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    }
+
+    if self.show_level {
+      let level_str = level.to_string();
+      let pad = " ".repeat(7 - level_str.chars().count());
+
+      let mut level_str = self.paint(self.theme.level_style(level), &level_str);
+
+      level_str += ":";
+      level_str += &pad;
+
+      message = format!("{} {}", level_str, message);
+    }
+
+    if self.show_timestamp {
+      let timestamp = record.timestamp().with_timezone(&Local).to_rfc2822();
+      let timestamp = self.paint(self.theme.timestamp_style(), &timestamp);
+      message = format!("{} - {}", timestamp, message);
+    }
+
+    message
+  }
+}
+
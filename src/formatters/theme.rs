@@ -0,0 +1,245 @@
+use std::error;
+use std::fmt;
+use super::super::Level;
+use super::{Color, Style};
+
+/// The styles a formatter uses to colour each part of a line.
+///
+/// A theme assigns a [`Style`](struct.Style.html) to each level, to the
+/// timestamp, and to context keys and values. The built-in
+/// [`dark`](#method.dark) theme is used by default, and
+/// [`light`](#method.light) and [`monochrome`](#method.monochrome) themes
+/// are also provided.
+///
+/// Themes can be loaded from a config string with
+/// [`parse`](#method.parse). Each line or `;` separated entry has the form
+/// `name = style`, where `name` is a level, `timestamp`, `key` or `value`,
+/// and `style` is parsed by [`Style::parse`](struct.Style.html#method.parse).
+/// Entries start from the dark theme unless a `base` entry names another
+/// built-in theme. Text after a `#` is a comment.
+///
+/// # Examples
+///
+/// ```rust
+/// # use quil::prelude::*;
+/// use quil::formatters::{Color, Style, Text, Theme};
+///
+/// let theme = Theme::parse("
+///   base = light
+///   info = bold blue   # stand out more
+///   key = italic
+/// ").unwrap();
+///
+/// assert_eq!(theme.level_style(Info), Style::new().fg(Color::Blue).bold());
+/// assert_eq!(theme.key_style(), Style::new().italic());
+/// assert_eq!(theme.level_style(Error), Theme::light().level_style(Error));
+///
+/// let text = Text::new().colored(true).theme(theme);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+  error: Style,
+  warn: Style,
+  info: Style,
+  verbose: Style,
+  debug: Style,
+  trace: Style,
+  timestamp: Style,
+  key: Style,
+  value: Style,
+}
+
+impl Theme {
+  /// A theme for terminals with a dark background. This is the default.
+  pub fn dark() -> Self {
+    Self {
+      error: Style::new().fg(Color::BrightRed).bold(),
+      warn: Style::new().fg(Color::BrightYellow),
+      info: Style::new().fg(Color::BrightGreen),
+      verbose: Style::new().fg(Color::BrightCyan),
+      debug: Style::new().fg(Color::BrightBlack),
+      trace: Style::new().fg(Color::BrightBlack).dimmed(),
+      timestamp: Style::new().fg(Color::BrightBlack),
+      key: Style::new().fg(Color::Cyan),
+      value: Style::new(),
+    }
+  }
+
+  /// A theme for terminals with a light background.
+  pub fn light() -> Self {
+    Self {
+      error: Style::new().fg(Color::Red).bold(),
+      warn: Style::new().fg(Color::Magenta).bold(),
+      info: Style::new().fg(Color::Blue),
+      verbose: Style::new().fg(Color::Cyan),
+      debug: Style::new().fg(Color::Black),
+      trace: Style::new().fg(Color::BrightBlack),
+      timestamp: Style::new().fg(Color::BrightBlack),
+      key: Style::new().fg(Color::Blue),
+      value: Style::new(),
+    }
+  }
+
+  /// A theme without colour, using only bold and dimmed text.
+  pub fn monochrome() -> Self {
+    Self {
+      error: Style::new().bold(),
+      warn: Style::new().bold(),
+      info: Style::new(),
+      verbose: Style::new(),
+      debug: Style::new().dimmed(),
+      trace: Style::new().dimmed(),
+      timestamp: Style::new().dimmed(),
+      key: Style::new().dimmed(),
+      value: Style::new(),
+    }
+  }
+
+  /// Parses a theme from a config string.
+  ///
+  /// # Arguments
+  ///
+  /// * `config` - Entries such as `"base = light; info = bold blue"`.
+  pub fn parse(config: &str) -> Result<Self, ParseThemeError> {
+    let mut theme = Self::dark();
+    let mut entries = Vec::new();
+    for (index, line) in config.lines().enumerate() {
+      let line = line.split('#').next().unwrap_or("");
+      for entry in line.split(';').map(str::trim).filter(|entry| !entry.is_empty()) {
+        entries.push((index + 1, entry));
+      }
+    }
+
+    for &(line, entry) in &entries {
+      let mut parts = entry.splitn(2, '=');
+      let name = parts.next().unwrap_or("").trim();
+      let value = match parts.next() {
+        Some(value) => value.trim(),
+        None => return Err(ParseThemeError::new(line, format!("expected `name = style`, found `{}`", entry))),
+      };
+      if name != "base" {
+        continue;
+      }
+      theme = match value {
+        "dark" => Self::dark(),
+        "light" => Self::light(),
+        "monochrome" => Self::monochrome(),
+        _ => return Err(ParseThemeError::new(line, format!("unknown base theme `{}`", value))),
+      };
+    }
+
+    for &(line, entry) in &entries {
+      let mut parts = entry.splitn(2, '=');
+      let name = parts.next().unwrap_or("").trim();
+      let value = parts.next().unwrap_or("").trim();
+      if name == "base" {
+        continue;
+      }
+      let style = Style::parse(value)
+        .map_err(|error| ParseThemeError::new(line, error.to_string()))?;
+      theme = match name {
+        "error" => theme.level(Level::Error, style),
+        "warn" => theme.level(Level::Warn, style),
+        "info" => theme.level(Level::Info, style),
+        "verbose" => theme.level(Level::Verbose, style),
+        "debug" => theme.level(Level::Debug, style),
+        "trace" => theme.level(Level::Trace, style),
+        "timestamp" => theme.timestamp(style),
+        "key" => theme.key(style),
+        "value" => theme.value(style),
+        _ => return Err(ParseThemeError::new(line, format!("unknown theme entry `{}`", name))),
+      };
+    }
+    Ok(theme)
+  }
+
+  /// Set the style for a level.
+  pub fn level(mut self, level: Level, style: Style) -> Self {
+    match level {
+      Level::Error => self.error = style,
+      Level::Warn => self.warn = style,
+      Level::Info => self.info = style,
+      Level::Verbose => self.verbose = style,
+      Level::Debug => self.debug = style,
+      Level::Trace => self.trace = style,
+    }
+    self
+  }
+
+  /// Set the style for timestamps.
+  pub fn timestamp(mut self, style: Style) -> Self {
+    self.timestamp = style;
+    self
+  }
+
+  /// Set the style for context keys.
+  pub fn key(mut self, style: Style) -> Self {
+    self.key = style;
+    self
+  }
+
+  /// Set the style for context values.
+  pub fn value(mut self, style: Style) -> Self {
+    self.value = style;
+    self
+  }
+
+  /// Returns the style for a level.
+  pub fn level_style(&self, level: Level) -> Style {
+    match level {
+      Level::Error => self.error,
+      Level::Warn => self.warn,
+      Level::Info => self.info,
+      Level::Verbose => self.verbose,
+      Level::Debug => self.debug,
+      Level::Trace => self.trace,
+    }
+  }
+
+  /// Returns the style for timestamps.
+  pub fn timestamp_style(&self) -> Style {
+    self.timestamp
+  }
+
+  /// Returns the style for context keys.
+  pub fn key_style(&self) -> Style {
+    self.key
+  }
+
+  /// Returns the style for context values.
+  pub fn value_style(&self) -> Style {
+    self.value
+  }
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self::dark()
+  }
+}
+
+/// The error returned when a theme config string cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseThemeError {
+  line: usize,
+  reason: String,
+}
+
+impl ParseThemeError {
+  fn new(line: usize, reason: String) -> Self {
+    Self { line, reason }
+  }
+
+  /// Returns the line of the config string the error is on, starting at 1.
+  pub fn line(&self) -> usize {
+    self.line
+  }
+}
+
+impl fmt::Display for ParseThemeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "invalid theme on line {}: {}", self.line, self.reason)
+  }
+}
+
+impl error::Error for ParseThemeError {}
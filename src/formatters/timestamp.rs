@@ -0,0 +1,33 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+
+/// The number of fractional second digits written in a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+  Seconds,
+  Millis,
+  Micros,
+  Nanos,
+}
+
+/// The timezone a timestamp is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+  /// UTC, written with a `Z` offset.
+  Utc,
+  /// The local timezone, written with a numeric offset such as `+02:00`.
+  Local,
+}
+
+/// Formats a time as an RFC 3339 timestamp.
+pub(crate) fn rfc3339(time: &DateTime<Utc>, precision: Precision, timezone: Timezone) -> String {
+  let format = match precision {
+    Precision::Seconds => SecondsFormat::Secs,
+    Precision::Millis => SecondsFormat::Millis,
+    Precision::Micros => SecondsFormat::Micros,
+    Precision::Nanos => SecondsFormat::Nanos,
+  };
+  match timezone {
+    Timezone::Utc => time.to_rfc3339_opts(format, true),
+    Timezone::Local => time.with_timezone(&Local).to_rfc3339_opts(format, false),
+  }
+}
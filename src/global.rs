@@ -0,0 +1,158 @@
+use std::error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use super::Context;
+use super::Error;
+use super::Level;
+use super::Logger;
+use super::Record;
+use super::Target;
+use super::targets::Dump;
+
+static GLOBAL: OnceLock<Logger> = OnceLock::new();
+static NOOP: OnceLock<Logger> = OnceLock::new();
+
+// Logs to the dump of the live `GlobalCapture`, if any. `CAPTURING` is set
+// while a capture is alive, and then takes priority over `GLOBAL`.
+static CAPTURE: OnceLock<Logger> = OnceLock::new();
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+// Held by each `GlobalCapture` so that tests capturing the global logger
+// at the same time run one after another.
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+struct Discard;
+
+impl Target for Discard {
+  fn log(&mut self, _: &Record) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+/// Sets the process wide logger returned by [`global`](fn.global.html) and
+/// used by the logging macros when no logger is given.
+///
+/// The global logger can only be set once. Its level, filter and target can
+/// still be changed through any clone of it.
+///
+/// # Arguments
+///
+/// * `logger` - The logger to use for the rest of the process.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// quil::set_global(logger).unwrap();
+///
+/// info!("user {} logged in", 42; session = "a1b2");
+/// quil::global().warn("disk almost full");
+///
+/// assert!(quil::set_global(Logger::new(Console::new(), context!{})).is_err());
+/// # }
+/// ```
+pub fn set_global(logger: Logger) -> Result<(), SetGlobalError> {
+  GLOBAL.set(logger).map_err(|_| SetGlobalError(()))
+}
+
+/// Returns the process wide logger.
+///
+/// Before [`set_global`](fn.set_global.html) is called this is a logger
+/// which discards every message, so libraries can log without checking
+/// whether the application has set up logging.
+pub fn global() -> Logger {
+  global_ref().clone()
+}
+
+/// Returns a reference to the process wide logger, without cloning it. The
+/// logging macros use this when no logger is given. See
+/// [`global`](fn.global.html).
+pub fn global_ref() -> &'static Logger {
+  if CAPTURING.load(Ordering::Acquire) {
+    return capture_logger();
+  }
+  match GLOBAL.get() {
+    Some(logger) => logger,
+    None => NOOP.get_or_init(|| Logger::new(Discard, Context::new())),
+  }
+}
+
+fn capture_logger() -> &'static Logger {
+  CAPTURE.get_or_init(|| Logger::new(Discard, Context::new()))
+}
+
+/// Replaces the global logger with one logging to a
+/// [`Dump`](targets/struct.Dump.html) until the returned guard is dropped.
+/// This is meant for tests of code which logs through the global logger.
+///
+/// Only one capture can be alive at a time. Tests calling `capture_global`
+/// in parallel wait for each other, but messages logged by other tests
+/// through the global logger while a capture is alive are captured too.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// fn connect() {
+///   warn!("retrying connection"; attempt = 2);
+/// }
+///
+/// let capture = quil::capture_global();
+/// connect();
+///
+/// let messages = capture.dump_messages();
+/// assert_eq!(messages[0].0, Warn);
+/// assert_eq!(messages[0].1, "retrying connection");
+/// assert_eq!(messages[0].2.get("attempt").unwrap(), &Value::I64(2));
+/// # }
+/// ```
+pub fn capture_global() -> GlobalCapture {
+  let lock = CAPTURE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+  let dump = Dump::new();
+  let logger = capture_logger();
+  logger.set_target(dump.clone());
+  logger.set_level(Level::Trace);
+  logger.clear_filter();
+  CAPTURING.store(true, Ordering::Release);
+  GlobalCapture { _lock: lock, dump }
+}
+
+/// Restores the global logger when dropped. Returned by
+/// [`capture_global`](fn.capture_global.html).
+pub struct GlobalCapture {
+  _lock: MutexGuard<'static, ()>,
+  dump: Dump,
+}
+
+impl GlobalCapture {
+  /// Removes and returns every message logged to the global logger since
+  /// the capture started or this method was last called.
+  pub fn dump_messages(&self) -> Vec<(Level, String, Context)> {
+    self.dump.dump_messages()
+  }
+}
+
+impl Drop for GlobalCapture {
+  fn drop(&mut self) {
+    CAPTURING.store(false, Ordering::Release);
+    capture_logger().set_target(Discard);
+  }
+}
+
+/// The error returned by [`set_global`](fn.set_global.html) when the global
+/// logger has already been set.
+#[derive(Debug)]
+pub struct SetGlobalError(());
+
+impl fmt::Display for SetGlobalError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "the global logger has already been set")
+  }
+}
+
+impl error::Error for SetGlobalError {}
@@ -1,4 +1,7 @@
+use std::convert::TryFrom;
+use std::error;
 use std::fmt;
+use std::str::FromStr;
 
 /// Log levels to be used with a [`Logger`](struct.Level.html).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -14,32 +17,99 @@ pub enum Level {
 impl fmt::Display for Level {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     use self::Level::*;
-    match self {
-      &Error => write!(f, "error"),
-      &Warn => write!(f, "warn"),
-      &Info => write!(f, "info"),
-      &Verbose => write!(f, "verbose"),
-      &Debug => write!(f, "debug"),
-      &Trace => write!(f, "trace"),
+    match *self {
+      Error => write!(f, "error"),
+      Warn => write!(f, "warn"),
+      Info => write!(f, "info"),
+      Verbose => write!(f, "verbose"),
+      Debug => write!(f, "debug"),
+      Trace => write!(f, "trace"),
     }
   }
 }
 
-impl<S> From<S> for Level
-where
-  S: AsRef<str>,
-{
-  fn from(string: S) -> Self {
+/// Parses a level name, ignoring case and surrounding whitespace.
+///
+/// Besides the level names, `warning` is accepted for `Warn`, and `err` and
+/// `fatal` for `Error`. The numbers `0` to `5` stand for `Error` to `Trace`.
+///
+/// # Examples
+///
+/// ```rust
+/// use quil::Level;
+///
+/// assert_eq!("Warning".parse::<Level>(), Ok(Level::Warn));
+/// assert_eq!("5".parse::<Level>(), Ok(Level::Trace));
+/// assert!("loud".parse::<Level>().is_err());
+/// ```
+impl FromStr for Level {
+  type Err = ParseLevelError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    use self::Level::*;
+    match s.trim().to_lowercase().as_str() {
+      "error" | "err" | "fatal" | "0" => Ok(Error),
+      "warn" | "warning" | "1" => Ok(Warn),
+      "info" | "2" => Ok(Info),
+      "verbose" | "3" => Ok(Verbose),
+      "debug" | "4" => Ok(Debug),
+      "trace" | "5" => Ok(Trace),
+      _ => Err(ParseLevelError {
+        level: s.to_string(),
+      }),
+    }
+  }
+}
+
+impl<'a> TryFrom<&'a str> for Level {
+  type Error = ParseLevelError;
+
+  fn try_from(s: &'a str) -> Result<Self, ParseLevelError> {
+    s.parse()
+  }
+}
+
+/// Converts the numbers `0` to `5` to `Error` to `Trace`.
+impl TryFrom<u8> for Level {
+  type Error = ParseLevelError;
+
+  fn try_from(n: u8) -> Result<Self, ParseLevelError> {
     use self::Level::*;
-    let s = string.as_ref();
-    match s {
-      "error" => Error,
-      "warn" => Warn,
-      "info" => Info,
-      "verbose" => Verbose,
-      "debug" => Debug,
-      "trace" => Trace,
-      _ => panic!("{} is not a valid level", s),
+    match n {
+      0 => Ok(Error),
+      1 => Ok(Warn),
+      2 => Ok(Info),
+      3 => Ok(Verbose),
+      4 => Ok(Debug),
+      5 => Ok(Trace),
+      _ => Err(ParseLevelError {
+        level: n.to_string(),
+      }),
     }
   }
 }
+
+/// The error returned when a level cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError {
+  level: String,
+}
+
+impl ParseLevelError {
+  /// Returns the text which could not be parsed.
+  pub fn level(&self) -> &str {
+    &self.level
+  }
+}
+
+impl fmt::Display for ParseLevelError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "invalid level \"{}\": expected error, warn, info, verbose, debug, trace or 0 to 5",
+      self.level
+    )
+  }
+}
+
+impl error::Error for ParseLevelError {}
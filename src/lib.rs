@@ -41,13 +41,13 @@
 //!
 //! Multi-target example:
 //!
-//! ```rust
+//! ```rust,no_run
 //! # #[macro_use] extern crate quil;
 //! # fn main() {
 //! # use quil::prelude::*;
 //! let logger = Logger::new(targets![
 //!   Console::new(),
-//!   JsonFile::open("path/to/logfile.json"),
+//!   JsonFile::open("path/to/logfile.json").unwrap()
 //! ], context!{ some_meta_key: "some_meta_value" });
 //! 
 //! logger.info("hello");
@@ -66,25 +66,68 @@
 //! _Log File_:
 //! 
 //! ```json
-//! { "level": "info", "message": "hello", "context": { "some_meta_key": "some_meta_value" } }
-//! { "level": "warn", "message": "world", "context": { "some_meta_key": "some_meta_value", "marker": "49" } }
+//! { "timestamp": "2017-11-07T23:55:42.118Z", "level": "info", "message": "hello", "context": { "some_meta_key": "some_meta_value" } }
+//! { "timestamp": "2017-11-07T23:55:42.118Z", "level": "warn", "message": "world", "context": { "some_meta_key": "some_meta_value", "marker": "49" } }
 //! ```
 
 extern crate chrono;
-extern crate colored;
+extern crate hostname;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "config")]
+extern crate serde;
+#[cfg(feature = "config")]
+extern crate serde_json;
+#[cfg(all(feature = "signals", unix))]
+extern crate signal_hook;
+#[cfg(feature = "config")]
+extern crate toml;
+#[cfg(feature = "tracing")]
+extern crate tracing_core;
+#[cfg(feature = "tracing")]
+extern crate tracing_subscriber;
 
 #[macro_use]
 mod context;
+#[macro_use]
+mod macros;
+mod error;
+mod filter;
+mod global;
 mod level;
 mod logger;
+mod panic_hook;
+mod record;
+mod scope;
+#[cfg(all(feature = "signals", unix))]
+mod signals;
+mod value;
 
 #[macro_use]
 pub mod targets;
+pub mod formatters;
+pub mod error_handlers;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "log")]
+pub mod facade;
+#[cfg(feature = "tracing")]
+pub mod tracing_layer;
 
 pub use targets::Target;
 pub use context::Context;
-pub use level::Level;
+pub use error::Error;
+pub use error_handlers::ErrorHandler;
+pub use filter::{Filter, ParseFilterError};
+pub use global::{capture_global, global, global_ref, set_global, GlobalCapture, SetGlobalError};
+pub use level::{Level, ParseLevelError};
 pub use logger::Logger;
+pub use panic_hook::{install_panic_hook, PanicHookOptions};
+pub use record::Record;
+pub use scope::{push_context, with_context, with_context_async, ContextGuard, WithContext};
+#[cfg(all(feature = "signals", unix))]
+pub use signals::{reopen_on_signal, ReopenOnSignalOptions, SignalListener};
+pub use value::Value;
 
 /// A convenience module that can be used to include commonly used quil types.
 ///
@@ -94,8 +137,9 @@ pub use logger::Logger;
 /// use quil::prelude::*;
 /// ```
 pub mod prelude {
-  pub use targets::{Console, JsonFile};
+  pub use targets::{Console, JsonFile, RollingFile};
   pub use level::Level::*;
   pub use context::Context;
   pub use logger::Logger;
+  pub use value::Value;
 }
@@ -1,7 +1,50 @@
-use std::sync::{Arc, Mutex, RwLock};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::error;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
 use super::Context;
+use super::Error;
+use super::ErrorHandler;
+use super::Filter;
 use super::Target;
 use super::Level;
+use super::ParseLevelError;
+use super::Record;
+use super::error_handlers::Stderr;
+use super::scope;
+
+// Ids handed to new loggers. Zero is never used, so it can stand for no
+// logger.
+#[cfg(feature = "log")]
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+  static LOGGING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns true while the current thread is inside a logger's target or
+/// error handler, or is the worker thread of an `AsyncTarget`.
+pub(crate) fn logging() -> bool {
+  LOGGING.with(Cell::get)
+}
+
+// Marks the current thread as logging until dropped, including while
+// unwinding from a panic in a target.
+pub(crate) struct Logging(bool);
+
+impl Logging {
+  pub(crate) fn enter() -> Self {
+    Logging(LOGGING.with(|logging| logging.replace(true)))
+  }
+}
+
+impl Drop for Logging {
+  fn drop(&mut self) {
+    let _ = LOGGING.try_with(|logging| logging.set(self.0));
+  }
+}
 
 /// Logger has methods for logging messages from your program. This struct is
 /// the heart of Quil. Logger is clonable and can be send across threads safely.
@@ -20,9 +63,17 @@ use super::Level;
 /// ```
 #[derive(Clone)]
 pub struct Logger {
-  target: Arc<Mutex<Box<Target + 'static>>>,
+  target: Arc<Mutex<Box<dyn Target + Send + 'static>>>,
   level: Arc<RwLock<Level>>,
+  filter: Arc<RwLock<Option<Filter>>>,
+  error_handler: Arc<Mutex<Box<dyn ErrorHandler + Send + 'static>>>,
   context: Arc<Context>,
+  // Counts calls to `reconfigure`, which bump it while holding the target's
+  // lock, so that a message admitted before one is checked again.
+  generation: Arc<AtomicUsize>,
+  // Shared by clones and sub-loggers, which also share the level.
+  #[cfg(feature = "log")]
+  id: usize,
 }
 
 impl Logger {
@@ -38,20 +89,28 @@ impl Logger {
   /// you use the [`context`](macro.ctx.html) macro to create your
   /// [`Context`](struct.Context.html).
   ///
+  /// The target must be `Send`, since clones of the logger can log to it
+  /// from any thread.
+  ///
   /// See [`Logger` examples](struct.Logger.html#examples) above.
   ///
   /// # Arguments
   /// * `target` - The target the logger will log to.
-  /// * `context` - The context that will be passed with each log message.
-  ///             to the target.
+  /// * `context` - The context that will be passed with each log message
+  ///   to the target.
   pub fn new<T>(target: T, context: Context) -> Self
   where
-    T: Target + 'static,
+    T: Target + Send + 'static,
   {
     Self {
       target: Arc::new(Mutex::new(Box::new(target))),
       level: Arc::new(RwLock::new(Level::Trace)),
+      filter: Arc::new(RwLock::new(None)),
+      error_handler: Arc::new(Mutex::new(Box::new(Stderr))),
       context: Arc::new(context),
+      generation: Arc::new(AtomicUsize::new(0)),
+      #[cfg(feature = "log")]
+      id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
     }
   }
 
@@ -68,6 +127,89 @@ impl Logger {
     L: Into<Level>,
   {
     *self.level.write().unwrap() = level.into();
+    #[cfg(feature = "log")]
+    ::facade::level_changed(self.id(), self.max_level());
+  }
+
+  /// Changes the logger's logging level to a level parsed from a string,
+  /// such as one read from an environment variable or a config file. The
+  /// level is left unchanged if the string is not a valid level. See
+  /// [`Level::from_str`](enum.Level.html#impl-FromStr-for-Level) for the
+  /// names accepted.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The name of the log level you'd like to set.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # #[macro_use] extern crate quil;
+  /// # fn main() {
+  /// # use quil::prelude::*;
+  /// let logger = Logger::new(Console::new(), context!{});
+  ///
+  /// logger.try_set_level("WARNING").unwrap();
+  /// assert_eq!(logger.level(), Warn);
+  ///
+  /// assert!(logger.try_set_level("loud").is_err());
+  /// assert_eq!(logger.level(), Warn);
+  /// # }
+  /// ```
+  pub fn try_set_level<S>(&self, level: S) -> Result<(), ParseLevelError>
+  where
+    S: AsRef<str>,
+  {
+    self.set_level(level.as_ref().parse::<Level>()?);
+    Ok(())
+  }
+
+  /// Returns the logger's current logging level.
+  pub fn level(&self) -> Level {
+    *self.level.read().unwrap()
+  }
+
+  /// Sets a filter which chooses the level for each message based on its
+  /// context. See [`Filter`](struct.Filter.html).
+  ///
+  /// Note that this will change the filter for all loggers that share the
+  /// same ancestor.
+  ///
+  /// # Arguments
+  ///
+  /// * `filter` - The filter you'd like to set.
+  pub fn set_filter(&self, filter: Filter) {
+    *self.filter.write().unwrap() = Some(filter);
+    #[cfg(feature = "log")]
+    ::facade::level_changed(self.id(), self.max_level());
+  }
+
+  /// Removes the logger's filter, leaving only its level.
+  ///
+  /// Note that this will remove the filter for all loggers that share the
+  /// same ancestor.
+  pub fn clear_filter(&self) {
+    *self.filter.write().unwrap() = None;
+    #[cfg(feature = "log")]
+    ::facade::level_changed(self.id(), self.max_level());
+  }
+
+  /// Returns the most verbose level any message could be logged at, taking
+  /// both the logger's level and its filter into account.
+  pub fn max_level(&self) -> Level {
+    let level = self.level();
+    match *self.filter.read().unwrap() {
+      Some(ref filter) => {
+        let level = filter.default_level().unwrap_or(level);
+        filter.max_level().map_or(level, |max| max.max(level))
+      }
+      None => level,
+    }
+  }
+
+  #[cfg(feature = "log")]
+  pub(crate) fn id(&self) -> usize {
+    self.id
   }
 
   /// Changes the logger target.
@@ -80,9 +222,58 @@ impl Logger {
   /// * `target` - The target you'd like the logger to use.
   pub fn set_target<T>(&self, target: T)
   where
-    T: Target + 'static,
+    T: Target + Send + 'static,
   {
-    *self.target.lock().unwrap() = Box::new(target);
+    *self.target.lock().unwrap_or_else(PoisonError::into_inner) = Box::new(target);
+  }
+
+  /// Changes the logger's level, filter and target together. The target's
+  /// lock is held throughout, so no message is written while only some of
+  /// them have changed, and a message checked against the old level and
+  /// filter is checked again before it is written to the new target. The
+  /// old target is flushed before it is dropped, and any error flushing it
+  /// is ignored.
+  ///
+  /// Note that this will change the level, filter and target for all
+  /// loggers that share the same ancestor.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The log level you'd like to set.
+  /// * `filter` - The filter you'd like to set, or `None` to remove it.
+  /// * `target` - The target you'd like the logger to use.
+  pub fn reconfigure<T>(&self, level: Level, filter: Option<Filter>, target: T)
+  where
+    T: Target + Send + 'static,
+  {
+    let mut old = {
+      let mut current = self.target.lock().unwrap_or_else(PoisonError::into_inner);
+      *self.level.write().unwrap() = level;
+      *self.filter.write().unwrap() = filter;
+      self.generation.fetch_add(1, Ordering::SeqCst);
+      mem::replace(&mut *current, Box::new(target))
+    };
+    #[cfg(feature = "log")]
+    ::facade::level_changed(self.id(), self.max_level());
+    let _logging = Logging::enter();
+    let _ = old.flush();
+  }
+
+  /// Changes the handler called when the logger's target fails to log a
+  /// message. By default errors are printed to stderr. See
+  /// [`error_handlers`](error_handlers/index.html) for the built in handlers.
+  ///
+  /// Note that this will change the error handler for all loggers that share
+  /// the same ancestor.
+  ///
+  /// # Arguments
+  ///
+  /// * `error_handler` - The error handler you'd like the logger to use.
+  pub fn set_error_handler<H>(&self, error_handler: H)
+  where
+    H: ErrorHandler + Send + 'static,
+  {
+    *self.error_handler.lock().unwrap_or_else(PoisonError::into_inner) = Box::new(error_handler);
   }
 
   /// Creates a new logger with an extended context.
@@ -140,7 +331,12 @@ impl Logger {
     Self {
       target: self.target.clone(),
       level: self.level.clone(),
+      filter: self.filter.clone(),
+      error_handler: self.error_handler.clone(),
       context: Arc::new(self.context.merge(context)),
+      generation: self.generation.clone(),
+      #[cfg(feature = "log")]
+      id: self.id,
     }
   }
 
@@ -155,6 +351,46 @@ impl Logger {
     self.log(Level::Error, message);
   }
 
+  /// Log an error message along with the error that caused it. The error's
+  /// text, type name and source chain are added to the message's context,
+  /// as described in
+  /// [`Context::from_error`](struct.Context.html#method.from_error).
+  ///
+  /// [`Console`](targets/struct.Console.html) prints the source chain
+  /// indented under the message, and
+  /// [`JsonFile`](targets/struct.JsonFile.html) writes it as an array.
+  ///
+  /// # Arguments
+  ///
+  /// * `error` - The error to log.
+  /// * `message` - A pointer the message to log.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # #[macro_use] extern crate quil;
+  /// # fn main() {
+  /// # use quil::prelude::*;
+  /// use std::fs;
+  ///
+  /// let logger = Logger::new(Console::new(), context!{ src: "root" });
+  ///
+  /// if let Err(error) = fs::read_to_string("missing.toml") {
+  ///   logger.error_with(&error, "failed to read the config");
+  /// }
+  /// # }
+  /// ```
+  pub fn error_with<E>(&self, error: &E, message: &str)
+  where
+    E: error::Error + ?Sized,
+  {
+    if Level::Error <= self.max_level() {
+      if let Some(admitted) = self.admit(Level::Error, Some(Context::from_error(error)), None) {
+        self.log_admitted(admitted.with_error_details(), message, None, None, None);
+      }
+    }
+  }
+
   /// Log a warning message.
   ///
   /// It's recommended to use this method only to log warnings.
@@ -224,6 +460,64 @@ impl Logger {
     self.log(Level::Trace, message);
   }
 
+  /// Flushes any messages buffered by the logger's target.
+  ///
+  /// For targets such as [`AsyncTarget`](targets/struct.AsyncTarget.html)
+  /// this blocks until every message logged so far has been written.
+  pub fn flush(&self) -> Result<(), Error> {
+    let _logging = Logging::enter();
+    self.target.lock().unwrap_or_else(PoisonError::into_inner).flush()
+  }
+
+  /// Closes and reopens any files the logger's targets write to. Call this
+  /// after an external tool such as logrotate has renamed a log file, so
+  /// that messages are written to a new file at the original path rather
+  /// than to the renamed one. See
+  /// [`Target::reopen`](targets/trait.Target.html#method.reopen).
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # #[macro_use] extern crate quil;
+  /// # fn main() {
+  /// # use quil::prelude::*;
+  /// # use std::fs;
+  /// use quil::targets::JsonFile;
+  ///
+  /// # let path = std::env::temp_dir().join("quil_reopen_example.json");
+  /// # let rotated = std::env::temp_dir().join("quil_reopen_example.json.1");
+  /// # let _ = fs::remove_file(&path);
+  /// let logger = Logger::new(JsonFile::open(path.clone()).unwrap(), context!{});
+  /// logger.info("before rotation");
+  ///
+  /// fs::rename(&path, &rotated).unwrap();
+  /// logger.reopen().unwrap();
+  /// logger.info("after rotation");
+  ///
+  /// assert!(fs::read_to_string(&rotated).unwrap().contains("before rotation"));
+  /// assert!(fs::read_to_string(&path).unwrap().contains("after rotation"));
+  /// # let _ = fs::remove_file(&path);
+  /// # let _ = fs::remove_file(&rotated);
+  /// # }
+  /// ```
+  pub fn reopen(&self) -> Result<(), Error> {
+    let _logging = Logging::enter();
+    self.target.lock().unwrap_or_else(PoisonError::into_inner).reopen()
+  }
+
+  /// Returns true if a message at the given level, logged from the given
+  /// module, would be logged. This takes the logger's filter into account
+  /// in the same way as logging a message does.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The log level to check.
+  /// * `module` - The module path the message would be logged from, which
+  ///   the filter falls back to if the context has no value for its key.
+  pub fn enabled(&self, level: Level, module: Option<&str>) -> bool {
+    self.admit(level, None, module).is_some()
+  }
+
   /// Log a message.
   ///
   /// It's recomended to use one of the level bound methods above instead of
@@ -237,17 +531,158 @@ impl Logger {
   where
     L: Into<Level>,
   {
-    let level = level.into();
-    if level > *self.level.read().unwrap() {
-      return;
+    self.log_at(level, message, None, None, None);
+  }
+
+  /// Log a message at a level parsed from a string. Nothing is logged if
+  /// the string is not a valid level. See
+  /// [`Level::from_str`](enum.Level.html#impl-FromStr-for-Level) for the
+  /// names accepted.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The name of the log level of the message.
+  /// * `message` - A pointer the message to log
+  pub fn try_log<S>(&self, level: S, message: &str) -> Result<(), ParseLevelError>
+  where
+    S: AsRef<str>,
+  {
+    self.log(level.as_ref().parse::<Level>()?, message);
+    Ok(())
+  }
+
+  /// Log a message along with the source location it was logged from. The
+  /// logging macros use this to record the file, line and module of each
+  /// call.
+  ///
+  /// Every message is logged with the context of any
+  /// [`with_context`](fn.with_context.html) scope the current thread is in,
+  /// merged under the logger's own context.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The log level of the message.
+  /// * `message` - A pointer the message to log.
+  /// * `file` - The source file the message was logged from.
+  /// * `line` - The line the message was logged from.
+  /// * `module` - The module path the message was logged from.
+  pub fn log_at<L>(
+    &self,
+    level: L,
+    message: &str,
+    file: Option<&str>,
+    line: Option<u32>,
+    module: Option<&str>,
+  ) where
+    L: Into<Level>,
+  {
+    if let Some(admitted) = self.admit(level.into(), None, module) {
+      self.log_admitted(admitted, message, file, line, module);
+    }
+  }
+
+  /// Checks a message against the logger's level and filter, once its
+  /// context has been merged with the logger's and the current scope's.
+  /// Used by the logging macros, which format the message only if it is
+  /// admitted. Use [`log_at`](#method.log_at) instead.
+  #[doc(hidden)]
+  pub fn admit(
+    &self,
+    level: Level,
+    context: Option<Context>,
+    module: Option<&str>,
+  ) -> Option<Admitted> {
+    let generation = self.generation.load(Ordering::SeqCst);
+    let context = self.message_context(context);
+    if !self.passes(level, &context, module) {
+      return None;
+    }
+    let context = match context {
+      Cow::Owned(context) => Some(context),
+      Cow::Borrowed(_) => None,
+    };
+    Some(Admitted {
+      level,
+      context,
+      error_details: false,
+      generation,
+    })
+  }
+
+  // Checks a message's level against the logger's level and filter.
+  fn passes(&self, level: Level, context: &Context, module: Option<&str>) -> bool {
+    let filter_level = match *self.filter.read().unwrap() {
+      Some(ref filter) => filter.level_for_message(context, module),
+      None => None,
+    };
+    level <= filter_level.unwrap_or_else(|| self.level())
+  }
+
+  /// Logs a message admitted by [`admit`](#method.admit) without checking
+  /// the level again.
+  #[doc(hidden)]
+  pub fn log_admitted(
+    &self,
+    admitted: Admitted,
+    message: &str,
+    file: Option<&str>,
+    line: Option<u32>,
+    module: Option<&str>,
+  ) {
+    let context = admitted.context.as_ref().unwrap_or(&self.context);
+    let mut record = Record::new(admitted.level, message, context).location(file, line, module);
+    record.error_details = admitted.error_details;
+    // A target which panicked is still logged to, so that the panic hook
+    // and later messages are not lost to a poisoned lock.
+    let _logging = Logging::enter();
+    let result = {
+      let mut target = self.target.lock().unwrap_or_else(PoisonError::into_inner);
+      // The logger was reconfigured since the message was admitted, and
+      // cannot be again until the lock is released.
+      if admitted.generation != self.generation.load(Ordering::SeqCst)
+        && !self.passes(admitted.level, context, module)
+      {
+        return;
+      }
+      target.log(&record)
+    };
+    if let Err(error) = result {
+      self.error_handler.lock().unwrap_or_else(PoisonError::into_inner).handle(error, &record);
     }
-    self
-      .target
-      .lock()
-      .unwrap()
-      .log(level, message, &self.context);
   }
+
+  // Merges the current scope's context under the logger's own, borrowing
+  // the logger's context when there is no scope.
+  fn message_context(&self, context: Option<Context>) -> Cow<'_, Context> {
+    let context = match context {
+      Some(context) => Cow::Owned(self.context.merge(context)),
+      None => Cow::Borrowed(&*self.context),
+    };
+    match scope::current() {
+      Some(scoped) => Cow::Owned(scoped.merge(context.into_owned())),
+      None => context,
+    }
+  }
+}
+
+/// A message which passed a logger's level and filter, with the context it
+/// will be logged with. Returned by `Logger::admit` for the logging macros.
+#[doc(hidden)]
+pub struct Admitted {
+  level: Level,
+  // `None` when the message is logged with the logger's own context.
+  context: Option<Context>,
+  error_details: bool,
+  // The logger's generation when the message was admitted.
+  generation: usize,
 }
 
-unsafe impl Send for Logger {}
-unsafe impl Sync for Logger {}
+impl Admitted {
+  /// Marks the context's `error_chain` and `backtrace` as added by Quil, so
+  /// that targets such as `Console` may print them as details.
+  #[doc(hidden)]
+  pub fn with_error_details(mut self) -> Self {
+    self.error_details = true;
+    self
+  }
+}
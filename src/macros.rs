@@ -0,0 +1,270 @@
+/// Logs a formatted message at a given level.
+///
+/// The message is given as a format string and arguments, as with
+/// `format!`. Extra context keys and values for this message alone can be
+/// given after a `;`. These are merged into the logger's context.
+///
+/// The message is only formatted, and the context only built, if the
+/// logger's level allows the message to be logged. The file, line and
+/// module of the call are added to the message's
+/// [`Record`](struct.Record.html).
+///
+/// The logger can be left out, in which case the message is logged to the
+/// [`global`](fn.global.html) logger. The format string must then be a
+/// string literal.
+///
+/// It's recommended to use one of the level bound macros such as
+/// [`info!`](macro.info.html) instead of `log!`.
+///
+/// These macros share their names with the `log` crate's. When both crates
+/// are used, import only one crate's macros with `#[macro_use]` and call the
+/// other's by path, as in `quil::info!`. See
+/// [`facade::install`](facade/fn.install.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// let (id, sid) = (42, "a1b2");
+///
+/// log!(logger, Info, "user {} logged in", id);
+/// log!(logger, Info, "user {} logged in", id; session = sid, attempts = 3);
+///
+/// // Logged to the global logger.
+/// log!(Info, "user {} logged in", id);
+///
+/// // Arguments are not evaluated when the level is filtered out.
+/// logger.set_level(Warn);
+/// log!(logger, Info, "{}", panic!("never formatted"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! log {
+  ($level:expr, $fmt:literal $($rest:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $level, $fmt $($rest)*)
+  );
+  ($logger:expr, $level:expr, $fmt:expr $(, $arg:expr)* ; $($key:ident = $value:expr),* $(,)?) => ({
+    let logger = &$logger;
+    let level = $level;
+    if level <= logger.max_level() {
+      let mut context = $crate::Context::new();
+      $(
+        context.set(stringify!($key), $value);
+      )*
+      if let Some(admitted) = logger.admit(level, Some(context), Some(module_path!())) {
+        logger.log_admitted(
+          admitted,
+          &format!($fmt $(, $arg)*),
+          Some(file!()),
+          Some(line!()),
+          Some(module_path!()),
+        );
+      }
+    }
+  });
+  ($logger:expr, $level:expr, $fmt:expr $(, $arg:expr)* $(,)?) => ({
+    let logger = &$logger;
+    let level = $level;
+    if let Some(admitted) = logger.admit(level, None, Some(module_path!())) {
+      logger.log_admitted(
+        admitted,
+        &format!($fmt $(, $arg)*),
+        Some(file!()),
+        Some(line!()),
+        Some(module_path!()),
+      );
+    }
+  });
+}
+
+/// Logs a formatted error message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// error!(logger, "failed to open {}", "db.sqlite"; retries = 3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! error {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Error, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Error, $($arg)+));
+}
+
+/// Logs a formatted error message along with the error that caused it. See
+/// [`Logger::error_with`](struct.Logger.html#method.error_with) and
+/// [`log!`](macro.log.html).
+///
+/// The error is given by reference after the logger, or first if the
+/// logger is left out.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// let path = "port.txt";
+/// if let Err(error) = "80a".parse::<u16>() {
+///   error_with!(logger, &error, "invalid port in {}", path; retries = 3);
+///
+///   // Logged to the global logger.
+///   error_with!(&error, "invalid port in {}", path);
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! error_with {
+  ($error:expr, $fmt:literal $($rest:tt)*) => (
+    $crate::error_with!(*$crate::global_ref(), $error, $fmt $($rest)*)
+  );
+  ($logger:expr, $error:expr, $fmt:expr $(, $arg:expr)* ; $($key:ident = $value:expr),* $(,)?) => ({
+    let logger = &$logger;
+    if $crate::Level::Error <= logger.max_level() {
+      let mut context = $crate::Context::from_error($error);
+      $(
+        context.set(stringify!($key), $value);
+      )*
+      let admitted = logger.admit($crate::Level::Error, Some(context), Some(module_path!()));
+      if let Some(admitted) = admitted {
+        logger.log_admitted(
+          admitted.with_error_details(),
+          &format!($fmt $(, $arg)*),
+          Some(file!()),
+          Some(line!()),
+          Some(module_path!()),
+        );
+      }
+    }
+  });
+  ($logger:expr, $error:expr, $fmt:expr $(, $arg:expr)* $(,)?) => ({
+    let logger = &$logger;
+    if $crate::Level::Error <= logger.max_level() {
+      let context = $crate::Context::from_error($error);
+      let admitted = logger.admit($crate::Level::Error, Some(context), Some(module_path!()));
+      if let Some(admitted) = admitted {
+        logger.log_admitted(
+          admitted.with_error_details(),
+          &format!($fmt $(, $arg)*),
+          Some(file!()),
+          Some(line!()),
+          Some(module_path!()),
+        );
+      }
+    }
+  });
+}
+
+/// Logs a formatted warning message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// warn!(logger, "disk {}% full", 91; mount = "/var");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! warn {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Warn, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Warn, $($arg)+));
+}
+
+/// Logs a formatted informational message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// # let (id, sid, addr) = (42, "a1b2", "127.0.0.1");
+/// info!(logger, "user {} logged in", id; session = sid, ip = addr);
+///
+/// // Logged to the global logger.
+/// info!("user {} logged in", id);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! info {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Info, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Info, $($arg)+));
+}
+
+/// Logs a formatted verbose message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// verbose!(logger, "loaded {} plugins", 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! verbose {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Verbose, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Verbose, $($arg)+));
+}
+
+/// Logs a formatted debug message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// debug!(logger, "cache miss for {:?}", "key"; shard = 2);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! debug {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Debug, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Debug, $($arg)+));
+}
+
+/// Logs a formatted trace message. See [`log!`](macro.log.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// trace!(logger, "entering {}", "parse");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! trace {
+  ($fmt:literal $($arg:tt)*) => (
+    $crate::log!(*$crate::global_ref(), $crate::Level::Trace, $fmt $($arg)*)
+  );
+  ($logger:expr, $($arg:tt)+) => ($crate::log!($logger, $crate::Level::Trace, $($arg)+));
+}
@@ -0,0 +1,155 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::panic::{self, PanicHookInfo};
+use std::thread;
+use super::Context;
+use super::Level;
+use super::Logger;
+use super::logger;
+
+/// Contains configuration options for the panic hook installed by
+/// [`install_panic_hook`](fn.install_panic_hook.html).
+///
+/// By default a backtrace is captured only when `RUST_BACKTRACE` or
+/// `RUST_LIB_BACKTRACE` ask for one, as with the standard hook, and the
+/// previous hook is not called.
+pub struct PanicHookOptions {
+  backtrace: bool,
+  chain: bool,
+}
+
+impl PanicHookOptions {
+  /// Creates a new instance of `PanicHookOptions`.
+  pub fn new() -> Self {
+    Self {
+      backtrace: false,
+      chain: false,
+    }
+  }
+
+  /// Enable/disable capturing a backtrace for every panic, whatever the
+  /// environment variables are set to.
+  pub fn backtrace(mut self, backtrace: bool) -> Self {
+    self.backtrace = backtrace;
+    self
+  }
+
+  /// Enable/disable calling the previously installed hook after the panic
+  /// has been logged. The standard hook prints the panic to stderr.
+  pub fn chain(mut self, chain: bool) -> Self {
+    self.chain = chain;
+    self
+  }
+
+  /// Replaces the current panic hook with one logging to the given logger.
+  ///
+  /// # Arguments
+  ///
+  /// * `logger` - The logger panics are logged to.
+  pub fn install(self, logger: Logger) {
+    let previous = if self.chain { Some(panic::take_hook()) } else { None };
+    let force_backtrace = self.backtrace;
+    panic::set_hook(Box::new(move |info| {
+      // A panic inside the logger's target, or on an async target's worker
+      // thread, would deadlock on the target's lock or a flush waiting for
+      // the worker, so it is left to the previous hook or stderr.
+      if logger::logging() {
+        match previous {
+          Some(ref previous) => previous(info),
+          None => eprintln!("{}", info),
+        }
+        return;
+      }
+      log_panic(&logger, info, force_backtrace);
+      if let Some(ref previous) = previous {
+        previous(info);
+      }
+    }));
+  }
+}
+
+impl Default for PanicHookOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Replaces the current panic hook with one logging each panic to the given
+/// logger at [`Error`](enum.Level.html#variant.Error), then flushing the
+/// logger's target. Use [`PanicHookOptions`](struct.PanicHookOptions.html)
+/// to capture backtraces or keep the previous hook.
+///
+/// The panic message is logged as the message, with the source location of
+/// the panic. The context has the `location`, `thread` and, if one was
+/// captured, the `backtrace` of the panic.
+///
+/// Flushing waits for targets such as
+/// [`AsyncTarget`](targets/struct.AsyncTarget.html) to write every message,
+/// so the panic is not lost if it brings the process down.
+///
+/// Panics in a target, including a target wrapped by an `AsyncTarget`, are
+/// not logged, as logging them could deadlock. They are passed to the
+/// previous hook when chained, or printed to stderr.
+///
+/// # Arguments
+///
+/// * `logger` - The logger panics are logged to.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::PanicHookOptions;
+///
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// quil::install_panic_hook(logger.clone());
+///
+/// // Or, keeping the standard output on stderr as well:
+/// PanicHookOptions::new()
+///   .backtrace(true)
+///   .chain(true)
+///   .install(logger);
+/// # let _ = std::panic::take_hook();
+/// # }
+/// ```
+pub fn install_panic_hook(logger: Logger) {
+  PanicHookOptions::new().install(logger);
+}
+
+fn log_panic(logger: &Logger, info: &PanicHookInfo, force_backtrace: bool) {
+  let payload = info.payload();
+  let message = match payload.downcast_ref::<&str>() {
+    Some(message) => *message,
+    None => match payload.downcast_ref::<String>() {
+      Some(message) => message.as_str(),
+      None => "Box<dyn Any>",
+    },
+  };
+
+  let mut context = Context::new();
+  if let Some(location) = info.location() {
+    context.set("location", location.to_string());
+  }
+  context.set("thread", thread::current().name().unwrap_or("<unnamed>"));
+  let backtrace = if force_backtrace {
+    Backtrace::force_capture()
+  } else {
+    Backtrace::capture()
+  };
+  if backtrace.status() == BacktraceStatus::Captured {
+    context.set("backtrace", backtrace.to_string());
+  }
+
+  let location = info.location();
+  if let Some(admitted) = logger.admit(Level::Error, Some(context), None) {
+    logger.log_admitted(
+      admitted.with_error_details(),
+      message,
+      location.map(|location| location.file()),
+      location.map(|location| location.line()),
+      None,
+    );
+  }
+  let _ = logger.flush();
+}
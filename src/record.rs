@@ -0,0 +1,132 @@
+use std::thread::{self, Thread, ThreadId};
+use chrono::{DateTime, Utc};
+use super::Context;
+use super::Level;
+
+/// A single logged message along with everything known about where and when
+/// it was logged. Records are created by [`Logger`](struct.Logger.html) and
+/// passed to its [`Target`](trait.Target.html).
+///
+/// The timestamp and thread are captured once when the record is created,
+/// so every target sees the same values even if the message is written
+/// later by another thread. The file, line and module are set when the
+/// message was logged through one of the logging macros or forwarded from
+/// the `log` crate or `tracing`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::Record;
+///
+/// let context = context!{ src: "root" };
+/// let record = Record::new(Info, "hello", &context)
+///   .location(Some("src/main.rs"), Some(7), None);
+///
+/// assert_eq!(record.level(), Info);
+/// assert_eq!(record.message(), "hello");
+/// assert_eq!(record.file(), Some("src/main.rs"));
+/// assert_eq!(record.line(), Some(7));
+/// assert_eq!(record.module(), None);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Record<'a> {
+  pub(crate) level: Level,
+  pub(crate) message: &'a str,
+  pub(crate) context: &'a Context,
+  pub(crate) timestamp: DateTime<Utc>,
+  pub(crate) thread: Thread,
+  pub(crate) file: Option<&'a str>,
+  pub(crate) line: Option<u32>,
+  pub(crate) module: Option<&'a str>,
+  // Set when the context's `error_chain` and `backtrace` were added by
+  // `error_with` or the panic hook, rather than by the caller.
+  pub(crate) error_details: bool,
+}
+
+impl<'a> Record<'a> {
+  /// Creates a new record, capturing the current time and thread.
+  ///
+  /// # Arguments
+  ///
+  /// * `level` - The log level the message is associated with.
+  /// * `message` - The message.
+  /// * `context` - The context containing meta data associated with the
+  ///   message.
+  pub fn new(level: Level, message: &'a str, context: &'a Context) -> Self {
+    Self {
+      level,
+      message,
+      context,
+      timestamp: Utc::now(),
+      thread: thread::current(),
+      file: None,
+      line: None,
+      module: None,
+      error_details: false,
+    }
+  }
+
+  /// Set the source file, line and module path the message was logged
+  /// from.
+  pub fn location(
+    mut self,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    module: Option<&'a str>,
+  ) -> Self {
+    self.file = file;
+    self.line = line;
+    self.module = module;
+    self
+  }
+
+  /// Returns the log level the message is associated with.
+  pub fn level(&self) -> Level {
+    self.level
+  }
+
+  /// Returns the message.
+  pub fn message(&self) -> &'a str {
+    self.message
+  }
+
+  /// Returns the context containing meta data associated with the message.
+  pub fn context(&self) -> &'a Context {
+    self.context
+  }
+
+  /// Returns the time the message was logged.
+  pub fn timestamp(&self) -> DateTime<Utc> {
+    self.timestamp
+  }
+
+  /// Returns the id of the thread the message was logged from.
+  pub fn thread_id(&self) -> ThreadId {
+    self.thread.id()
+  }
+
+  /// Returns the name of the thread the message was logged from, if it has
+  /// one.
+  pub fn thread_name(&self) -> Option<&str> {
+    self.thread.name()
+  }
+
+  /// Returns the source file the message was logged from, if known.
+  pub fn file(&self) -> Option<&'a str> {
+    self.file
+  }
+
+  /// Returns the line the message was logged from, if known.
+  pub fn line(&self) -> Option<u32> {
+    self.line
+  }
+
+  /// Returns the module path the message was logged from, if known.
+  pub fn module(&self) -> Option<&'a str> {
+    self.module
+  }
+}
@@ -0,0 +1,163 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task;
+use super::Context;
+
+thread_local! {
+  // Each entry is the merge of every context pushed before it, so the top
+  // of the stack is the whole scoped context.
+  static STACK: RefCell<Vec<Rc<Context>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the context of every scope the current thread is in, or `None`
+/// outside of any scope.
+pub(crate) fn current() -> Option<Rc<Context>> {
+  STACK.with(|stack| stack.borrow().last().cloned())
+}
+
+/// Runs a closure with a context added to every message logged on the
+/// current thread until it returns.
+///
+/// Scopes can be nested, and values in inner scopes take precedence over
+/// outer ones. Contexts of loggers and of individual messages take
+/// precedence over scoped contexts.
+///
+/// # Arguments
+///
+/// * `context` - The context to add to messages.
+/// * `f` - The closure to run.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::targets::Dump;
+///
+/// let dump = Dump::new();
+/// let logger = Logger::new(dump.clone(), context!{ src: "api" });
+///
+/// fn handle(logger: &Logger) {
+///   logger.info("handled");
+/// }
+///
+/// quil::with_context(context!{ request_id: "a1b2" }, || handle(&logger));
+///
+/// let messages = dump.dump_messages();
+/// assert_eq!(messages[0].2.get("request_id").unwrap(), "a1b2");
+/// assert_eq!(messages[0].2.get("src").unwrap(), "api");
+/// # }
+/// ```
+pub fn with_context<F, R>(context: Context, f: F) -> R
+where
+  F: FnOnce() -> R,
+{
+  let _guard = push_context(context);
+  f()
+}
+
+/// Adds a context to every message logged on the current thread until the
+/// returned guard is dropped. See [`with_context`](fn.with_context.html).
+///
+/// Guards should be dropped in the reverse order they were created in.
+/// Dropping a guard also ends any scope pushed after it.
+///
+/// # Arguments
+///
+/// * `context` - The context to add to messages.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// let _guard = quil::push_context(context!{ job: "cleanup" });
+/// logger.info("started");
+/// # }
+/// ```
+pub fn push_context(context: Context) -> ContextGuard {
+  let depth = STACK.with(|stack| {
+    let mut stack = stack.borrow_mut();
+    let merged = match stack.last() {
+      Some(top) => top.merge(context),
+      None => context,
+    };
+    stack.push(Rc::new(merged));
+    stack.len() - 1
+  });
+  ContextGuard {
+    depth,
+    _not_send: PhantomData,
+  }
+}
+
+/// Ends a scope when dropped. Returned by
+/// [`push_context`](fn.push_context.html).
+pub struct ContextGuard {
+  depth: usize,
+  // The guard refers to the current thread's stack.
+  _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+  fn drop(&mut self) {
+    let _ = STACK.try_with(|stack| stack.borrow_mut().truncate(self.depth));
+  }
+}
+
+/// Runs a future with a context added to every message logged while it is
+/// polled, wherever it is polled from. This carries the context across
+/// `.await` points and between the threads of a multi-threaded executor.
+///
+/// # Arguments
+///
+/// * `context` - The context to add to messages.
+/// * `future` - The future to run.
+///
+/// # Examples
+///
+/// ```rust,edition2021
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # let logger = Logger::new(Console::new(), context!{});
+/// let task = quil::with_context_async(context!{ request_id: "a1b2" }, async move {
+///   logger.info("handling request");
+/// });
+/// # let _ = task;
+/// # }
+/// ```
+pub fn with_context_async<F>(context: Context, future: F) -> WithContext<F>
+where
+  F: Future,
+{
+  WithContext { future, context }
+}
+
+/// A future which adds a context to messages logged while it is polled.
+/// Returned by [`with_context_async`](fn.with_context_async.html).
+pub struct WithContext<F> {
+  future: F,
+  context: Context,
+}
+
+impl<F> Future for WithContext<F>
+where
+  F: Future,
+{
+  type Output = F::Output;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> task::Poll<F::Output> {
+    // The future is never moved out of `self`, and the context is not
+    // pinned.
+    let this = unsafe { self.get_unchecked_mut() };
+    let _guard = push_context(this.context.clone());
+    unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx)
+  }
+}
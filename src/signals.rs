@@ -0,0 +1,136 @@
+use std::io;
+use std::thread::{self, JoinHandle};
+use signal_hook::consts::{SIGHUP, SIGUSR1};
+use signal_hook::iterator::{Handle, Signals};
+use super::Context;
+use super::Logger;
+
+/// Contains configuration options for the listener started by
+/// [`reopen_on_signal`](fn.reopen_on_signal.html).
+///
+/// By default files are reopened on both `SIGHUP` and `SIGUSR1`.
+pub struct ReopenOnSignalOptions {
+  sighup: bool,
+  sigusr1: bool,
+}
+
+impl ReopenOnSignalOptions {
+  /// Creates a new instance of `ReopenOnSignalOptions`.
+  pub fn new() -> Self {
+    Self {
+      sighup: true,
+      sigusr1: true,
+    }
+  }
+
+  /// Enable/disable reopening files when the process receives `SIGHUP`.
+  pub fn sighup(mut self, sighup: bool) -> Self {
+    self.sighup = sighup;
+    self
+  }
+
+  /// Enable/disable reopening files when the process receives `SIGUSR1`.
+  pub fn sigusr1(mut self, sigusr1: bool) -> Self {
+    self.sigusr1 = sigusr1;
+    self
+  }
+
+  /// Starts a thread which reopens the logger's files each time the process
+  /// receives one of the signals enabled.
+  ///
+  /// # Arguments
+  ///
+  /// * `logger` - The logger whose files are reopened.
+  pub fn listen(self, logger: Logger) -> Result<SignalListener, io::Error> {
+    let mut ids = Vec::new();
+    if self.sighup {
+      ids.push(SIGHUP);
+    }
+    if self.sigusr1 {
+      ids.push(SIGUSR1);
+    }
+    let mut signals = Signals::new(&ids)?;
+    let handle = signals.handle();
+    let spawned = thread::Builder::new()
+      .name("quil-reopen".to_string())
+      .spawn(move || {
+        for _ in signals.forever() {
+          if let Err(error) = logger.reopen() {
+            let mut context = Context::new();
+            context.set("error", error.to_string());
+            logger.ctx(context).error("failed to reopen log files");
+          }
+        }
+      });
+    match spawned {
+      Ok(worker) => Ok(SignalListener {
+        handle,
+        worker: Some(worker),
+      }),
+      Err(error) => {
+        handle.close();
+        Err(error)
+      }
+    }
+  }
+}
+
+impl Default for ReopenOnSignalOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Reopens the files a logger writes to, with
+/// [`Logger::reopen`](struct.Logger.html#method.reopen), each time the
+/// process receives `SIGHUP` or `SIGUSR1`. This lets an external tool such
+/// as logrotate rename log files and signal the process to start new ones.
+/// Use [`ReopenOnSignalOptions`](struct.ReopenOnSignalOptions.html) to
+/// listen for only one of the signals.
+///
+/// If a file cannot be reopened, an error is logged to the logger with the
+/// reason under the `error` key. Targets which reopened successfully are
+/// still reopened.
+///
+/// The listener stops when the returned
+/// [`SignalListener`](struct.SignalListener.html) is dropped. Only available
+/// on Unix with the `signals` feature.
+///
+/// # Arguments
+///
+/// * `logger` - The logger whose files are reopened.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::targets::JsonFile;
+///
+/// # let path = std::env::temp_dir().join("quil_reopen_on_signal_example.json");
+/// let logger = Logger::new(JsonFile::open(path).unwrap(), context!{});
+/// let _listener = quil::reopen_on_signal(logger.clone()).unwrap();
+///
+/// logger.info("started");
+/// # }
+/// ```
+pub fn reopen_on_signal(logger: Logger) -> Result<SignalListener, io::Error> {
+  ReopenOnSignalOptions::new().listen(logger)
+}
+
+/// Stops listening for signals when dropped. Returned by
+/// [`reopen_on_signal`](fn.reopen_on_signal.html).
+pub struct SignalListener {
+  handle: Handle,
+  worker: Option<JoinHandle<()>>,
+}
+
+impl Drop for SignalListener {
+  fn drop(&mut self) {
+    self.handle.close();
+    if let Some(worker) = self.worker.take() {
+      let _ = worker.join();
+    }
+  }
+}
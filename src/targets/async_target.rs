@@ -0,0 +1,482 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle, Thread};
+use chrono::{DateTime, Utc};
+use super::super::Context;
+use super::super::Error;
+use super::super::ErrorHandler;
+use super::super::Level;
+use super::super::Record;
+use super::super::error_handlers::Stderr;
+use super::super::logger::Logging;
+use super::Target;
+
+/// What an [`AsyncTarget`](struct.AsyncTarget.html) does with a message
+/// when its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+  /// Block the logging thread until the worker makes room.
+  Block,
+  /// Discard the message being logged.
+  DropNewest,
+  /// Discard the oldest message in the queue to make room.
+  DropOldest,
+}
+
+/// Contains configuration options for an `AsyncTarget` object.
+///
+/// By default the queue holds 1024 messages and blocks when full, and
+/// errors from the wrapped target are printed to stderr.
+pub struct AsyncTargetOptions {
+  capacity: usize,
+  overflow: Overflow,
+  error_handler: Box<dyn ErrorHandler + Send>,
+}
+
+impl AsyncTargetOptions {
+  /// Creates a new instance of `AsyncTargetOptions`.
+  pub fn new() -> Self {
+    Self {
+      capacity: 1024,
+      overflow: Overflow::Block,
+      error_handler: Box::new(Stderr),
+    }
+  }
+
+  /// Set the number of messages the queue can hold.
+  pub fn capacity(mut self, capacity: usize) -> Self {
+    self.capacity = capacity.max(1);
+    self
+  }
+
+  /// Set what happens to messages when the queue is full.
+  pub fn overflow(mut self, overflow: Overflow) -> Self {
+    self.overflow = overflow;
+    self
+  }
+
+  /// Set the error handler used when the wrapped target fails to log a
+  /// message. Errors happen on the worker thread, so they can not be
+  /// returned to the logger.
+  ///
+  /// The handler is also given errors from the flushes nobody waits on: the
+  /// last flush when the `AsyncTarget` is dropped and the flush done by an
+  /// [`AsyncGuard`](struct.AsyncGuard.html). Their record is an `Error`
+  /// with the message `"failed to flush async target"`.
+  pub fn error_handler<H>(mut self, error_handler: H) -> Self
+  where
+    H: ErrorHandler + Send + 'static,
+  {
+    self.error_handler = Box::new(error_handler);
+    self
+  }
+
+  /// Create an `AsyncTarget` wrapping the given target from the options
+  /// set. This starts the worker thread.
+  ///
+  /// # Arguments
+  ///
+  /// * `target` - The target the worker thread will log to.
+  pub fn build<T>(self, target: T) -> AsyncTarget
+  where
+    T: Target + Send + 'static,
+  {
+    let shared = Arc::new(Shared {
+      state: Mutex::new(State {
+        entries: VecDeque::with_capacity(self.capacity),
+        queued: 0,
+        next_ticket: 0,
+        completed: 0,
+        errors: HashMap::new(),
+        closed: false,
+        stopped: false,
+        panicked: false,
+      }),
+      not_empty: Condvar::new(),
+      not_full: Condvar::new(),
+      flushed: Condvar::new(),
+      dropped: AtomicUsize::new(0),
+      capacity: self.capacity,
+      overflow: self.overflow,
+      error_handler: Mutex::new(self.error_handler),
+    });
+
+    let worker_shared = shared.clone();
+    let worker = thread::Builder::new()
+      .name("quil-async".to_string())
+      .spawn(move || run(target, &worker_shared))
+      .expect("failed to spawn quil async worker thread");
+
+    AsyncTarget {
+      shared,
+      worker: Some(worker),
+    }
+  }
+}
+
+impl Default for AsyncTargetOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A target that hands messages to a worker thread through a bounded queue,
+/// so the wrapped target's I/O does not run on the logging thread. Records
+/// keep the timestamp and thread they were logged with.
+///
+/// [`flush`](struct.AsyncHandle.html#method.flush) blocks until every message
+/// queued before it has been logged and the wrapped target flushed. When the
+/// `AsyncTarget` is dropped the queue is drained before the worker exits.
+/// If the wrapped target panics the worker stops; messages logged after that
+/// are counted as dropped and flushing returns an error.
+/// Since the target is usually owned by a [`Logger`](../struct.Logger.html),
+/// an [`AsyncHandle`](struct.AsyncHandle.html) can be taken before handing
+/// it over.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use std::fs;
+/// use quil::targets::{AsyncTargetOptions, Overflow};
+///
+/// # let path = std::env::temp_dir().join("quil_async_target_example.json");
+/// # let _ = fs::remove_file(&path);
+/// let target = AsyncTargetOptions::new()
+///   .capacity(256)
+///   .overflow(Overflow::DropOldest)
+///   .build(JsonFile::open(path.clone()).unwrap());
+/// let handle = target.handle();
+///
+/// let logger = Logger::new(target, context!{ src: "root" });
+/// logger.info("hello");
+///
+/// // Block until the worker has written everything logged so far.
+/// handle.flush().unwrap();
+///
+/// assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+/// assert_eq!(handle.dropped(), 0);
+/// # }
+/// ```
+pub struct AsyncTarget {
+  shared: Arc<Shared>,
+  worker: Option<JoinHandle<()>>,
+}
+
+impl AsyncTarget {
+  /// Creates a new `AsyncTarget` with default options wrapping the given
+  /// target.
+  ///
+  /// # Arguments
+  ///
+  /// * `target` - The target the worker thread will log to.
+  pub fn new<T>(target: T) -> Self
+  where
+    T: Target + Send + 'static,
+  {
+    AsyncTargetOptions::new().build(target)
+  }
+
+  /// Returns a handle that can flush the queue and read the dropped message
+  /// count after the target has been given to a logger.
+  pub fn handle(&self) -> AsyncHandle {
+    AsyncHandle {
+      shared: self.shared.clone(),
+    }
+  }
+}
+
+impl Target for AsyncTarget {
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    self.shared.push(record);
+    Ok(())
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.shared.flush()
+  }
+
+  fn reopen(&mut self) -> Result<(), Error> {
+    self.shared.reopen()
+  }
+}
+
+impl Drop for AsyncTarget {
+  fn drop(&mut self) {
+    self.shared.lock().closed = true;
+    self.shared.not_empty.notify_all();
+    if let Some(worker) = self.worker.take() {
+      let _ = worker.join();
+    }
+  }
+}
+
+/// A handle to the queue of an [`AsyncTarget`](struct.AsyncTarget.html).
+#[derive(Clone)]
+pub struct AsyncHandle {
+  shared: Arc<Shared>,
+}
+
+impl AsyncHandle {
+  /// Blocks until every message queued before the call has been logged by
+  /// the worker and the wrapped target has been flushed. Returns the error
+  /// if flushing the wrapped target failed, or if the worker has stopped
+  /// after a panic.
+  pub fn flush(&self) -> Result<(), Error> {
+    self.shared.flush()
+  }
+
+  /// Returns the number of messages discarded because the queue was full or
+  /// the worker had stopped.
+  pub fn dropped(&self) -> usize {
+    self.shared.dropped.load(Ordering::SeqCst)
+  }
+
+  /// Returns a guard which flushes the queue when it is dropped. Keeping
+  /// the guard alive in `main` ensures queued messages are written before
+  /// the process exits. A failed flush is passed to the target's error
+  /// handler.
+  pub fn guard(&self) -> AsyncGuard {
+    AsyncGuard {
+      handle: self.clone(),
+    }
+  }
+}
+
+/// Flushes an [`AsyncTarget`](struct.AsyncTarget.html)'s queue when
+/// dropped. Created by [`AsyncHandle::guard`](struct.AsyncHandle.html#method.guard).
+pub struct AsyncGuard {
+  handle: AsyncHandle,
+}
+
+impl Drop for AsyncGuard {
+  fn drop(&mut self) {
+    if let Err(error) = self.handle.flush() {
+      self.handle.shared.flush_failed(error);
+    }
+  }
+}
+
+enum Entry {
+  Message(OwnedRecord),
+  Flush(u64),
+  Reopen(u64),
+}
+
+struct OwnedRecord {
+  level: Level,
+  message: String,
+  context: Context,
+  timestamp: DateTime<Utc>,
+  thread: Thread,
+  file: Option<String>,
+  line: Option<u32>,
+  module: Option<String>,
+  error_details: bool,
+}
+
+impl OwnedRecord {
+  fn new(record: &Record) -> Self {
+    Self {
+      level: record.level,
+      message: record.message.to_string(),
+      context: record.context.clone(),
+      timestamp: record.timestamp,
+      thread: record.thread.clone(),
+      file: record.file.map(str::to_string),
+      line: record.line,
+      module: record.module.map(str::to_string),
+      error_details: record.error_details,
+    }
+  }
+
+  fn record(&self) -> Record<'_> {
+    Record {
+      level: self.level,
+      message: &self.message,
+      context: &self.context,
+      timestamp: self.timestamp,
+      thread: self.thread.clone(),
+      file: self.file.as_deref(),
+      line: self.line,
+      module: self.module.as_deref(),
+      error_details: self.error_details,
+    }
+  }
+}
+
+struct State {
+  entries: VecDeque<Entry>,
+  queued: usize,
+  next_ticket: u64,
+  // Tickets are handled in order, so every ticket up to `completed` is done.
+  completed: u64,
+  // Errors of completed tickets not yet taken by their waiter.
+  errors: HashMap<u64, Error>,
+  closed: bool,
+  stopped: bool,
+  panicked: bool,
+}
+
+struct Shared {
+  state: Mutex<State>,
+  not_empty: Condvar,
+  not_full: Condvar,
+  flushed: Condvar,
+  dropped: AtomicUsize,
+  capacity: usize,
+  overflow: Overflow,
+  error_handler: Mutex<Box<dyn ErrorHandler + Send>>,
+}
+
+impl Shared {
+  fn lock(&self) -> MutexGuard<'_, State> {
+    self.state.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  fn push(&self, record: &Record) {
+    let mut state = self.lock();
+    while state.queued >= self.capacity && !state.stopped {
+      match self.overflow {
+        Overflow::Block => {
+          state = self.not_full.wait(state).unwrap_or_else(PoisonError::into_inner)
+        }
+        Overflow::DropNewest => {
+          self.dropped.fetch_add(1, Ordering::SeqCst);
+          return;
+        }
+        Overflow::DropOldest => {
+          let oldest = state
+            .entries
+            .iter()
+            .position(|entry| matches!(*entry, Entry::Message(..)));
+          if let Some(oldest) = oldest {
+            state.entries.remove(oldest);
+            state.queued -= 1;
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+          }
+        }
+      }
+    }
+    if state.stopped {
+      self.dropped.fetch_add(1, Ordering::SeqCst);
+      return;
+    }
+    state.entries.push_back(Entry::Message(OwnedRecord::new(record)));
+    state.queued += 1;
+    self.not_empty.notify_one();
+  }
+
+  fn flush(&self) -> Result<(), Error> {
+    self.wait_for(Entry::Flush)
+  }
+
+  fn reopen(&self) -> Result<(), Error> {
+    self.wait_for(Entry::Reopen)
+  }
+
+  // Queues an entry behind every message logged so far, and blocks until
+  // the worker has handled it.
+  fn wait_for(&self, entry: fn(u64) -> Entry) -> Result<(), Error> {
+    let mut state = self.lock();
+    state.next_ticket += 1;
+    let ticket = state.next_ticket;
+    state.entries.push_back(entry(ticket));
+    self.not_empty.notify_one();
+    while state.completed < ticket && !state.stopped {
+      state = self.flushed.wait(state).unwrap_or_else(PoisonError::into_inner);
+    }
+    if let Some(error) = state.errors.remove(&ticket) {
+      return Err(error);
+    }
+    // A worker which stopped normally flushed everything before exiting.
+    if state.completed < ticket && state.panicked {
+      return Err(Error::Other("async worker thread panicked".to_string()));
+    }
+    Ok(())
+  }
+
+  fn complete(&self, ticket: u64, result: Result<(), Error>) {
+    let mut state = self.lock();
+    state.completed = ticket;
+    if let Err(error) = result {
+      state.errors.insert(ticket, error);
+    }
+    self.flushed.notify_all();
+  }
+
+  fn handle_error(&self, error: Error, record: &Record) {
+    self
+      .error_handler
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .handle(error, record);
+  }
+
+  fn flush_failed(&self, error: Error) {
+    let context = Context::new();
+    self.handle_error(error, &Record::new(Level::Error, "failed to flush async target", &context));
+  }
+}
+
+// Marks the worker as stopped when it exits, including while unwinding from
+// a panic in the wrapped target, so that loggers and flushes waiting on it
+// do not block forever.
+struct Stopped<'a>(&'a Shared);
+
+impl<'a> Drop for Stopped<'a> {
+  fn drop(&mut self) {
+    let mut state = self.0.lock();
+    state.stopped = true;
+    state.panicked = thread::panicking();
+    self.0.not_full.notify_all();
+    self.0.flushed.notify_all();
+  }
+}
+
+fn run<T>(mut target: T, shared: &Shared)
+where
+  T: Target,
+{
+  // The worker only ever runs inside a target. A panic hook logging from
+  // here would wait on the worker's own queue, or on a logger blocked in a
+  // flush waiting for the worker.
+  let _logging = Logging::enter();
+  let _stopped = Stopped(shared);
+  loop {
+    let entry = {
+      let mut state = shared.lock();
+      loop {
+        if let Some(entry) = state.entries.pop_front() {
+          if let Entry::Message(..) = entry {
+            state.queued -= 1;
+            shared.not_full.notify_one();
+          }
+          break entry;
+        }
+        if state.closed {
+          drop(state);
+          if let Err(error) = target.flush() {
+            shared.flush_failed(error);
+          }
+          return;
+        }
+        state = shared.not_empty.wait(state).unwrap_or_else(PoisonError::into_inner);
+      }
+    };
+
+    match entry {
+      Entry::Message(record) => {
+        let record = record.record();
+        if let Err(error) = target.log(&record) {
+          shared.handle_error(error, &record);
+        }
+      }
+      Entry::Flush(ticket) => shared.complete(ticket, target.flush()),
+      Entry::Reopen(ticket) => shared.complete(ticket, target.reopen()),
+    }
+  }
+}
@@ -1,16 +1,141 @@
-use colored::*;
-use chrono::Local;
+use std::env;
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
 use super::super::Context;
+use super::super::Error;
 use super::super::Level;
-use super::Target;
+use super::super::Record;
+use super::super::Value;
+use super::super::formatters::{Formatter, Text, Theme};
+use super::{Target, Writer, WriterOptions};
+
+/// The stream a [`Console`](struct.Console.html) writes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+  /// Write every message to stdout.
+  Stdout,
+  /// Write every message to stderr.
+  Stderr,
+  /// Write errors and warnings to stderr and everything else to stdout.
+  Split,
+}
+
+impl Stream {
+  /// Returns whether a message at the given level is written to stderr.
+  pub fn uses_stderr(self, level: Level) -> bool {
+    match self {
+      Stream::Stdout => false,
+      Stream::Stderr => true,
+      Stream::Split => level <= Level::Warn,
+    }
+  }
+}
+
+/// When a [`Console`](struct.Console.html) writes colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+  /// Write colour if the stream is a terminal. Setting `NO_COLOR` to a
+  /// non-empty value disables colour, and setting `CLICOLOR_FORCE` to
+  /// anything other than `0` enables it even if the stream is not a
+  /// terminal. `CLICOLOR_FORCE` takes priority over `NO_COLOR`.
+  Auto,
+  /// Always write colour.
+  Always,
+  /// Never write colour.
+  Never,
+}
+
+impl ColorMode {
+  /// Returns whether colour is written, given the values of the
+  /// `CLICOLOR_FORCE` and `NO_COLOR` environment variables and whether the
+  /// stream is a terminal. Only `Auto` depends on them.
+  ///
+  /// # Arguments
+  ///
+  /// * `clicolor_force` - The value of `CLICOLOR_FORCE`, if set.
+  /// * `no_color` - The value of `NO_COLOR`, if set.
+  /// * `is_terminal` - Whether the stream is a terminal.
+  pub fn enabled(
+    self,
+    clicolor_force: Option<&OsStr>,
+    no_color: Option<&OsStr>,
+    is_terminal: bool,
+  ) -> bool {
+    match self {
+      ColorMode::Always => true,
+      ColorMode::Never => false,
+      ColorMode::Auto => {
+        if clicolor_force.is_some_and(|force| force != "0") {
+          return true;
+        }
+        if no_color.is_some_and(|no_color| !no_color.is_empty()) {
+          return false;
+        }
+        is_terminal
+      }
+    }
+  }
+
+  fn enabled_for<S>(self, stream: &S) -> bool
+  where
+    S: IsTerminal,
+  {
+    self.enabled(
+      env::var_os("CLICOLOR_FORCE").as_deref(),
+      env::var_os("NO_COLOR").as_deref(),
+      stream.is_terminal(),
+    )
+  }
+}
 
 /// Contains configuration options for a `Console` object.
 /// `ConsoleOptions` can be used to create `Console` objects with custom
 /// settings. All options default to false.
+///
+/// The layout of each line can be replaced entirely by setting a formatter,
+/// such as a [`Template`](../formatters/struct.Template.html). The
+/// `show_*` and `theme` options only apply when no formatter is set.
+///
+/// Messages are written to stdout by default, and coloured only if the
+/// stream is a terminal. See [`Stream`](enum.Stream.html) and
+/// [`ColorMode`](enum.ColorMode.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::targets::{ColorMode, ConsoleOptions, Stream};
+///
+/// use quil::formatters::Theme;
+///
+/// let console = ConsoleOptions::new()
+///   .show_level(true)
+///   .theme(Theme::light())
+///   .stream(Stream::Split)
+///   .color(ColorMode::Never)
+///   .build();
+///
+/// let logger = Logger::new(console, context!{});
+/// logger.info("written to stdout");
+/// logger.error("written to stderr");
+/// # }
+/// ```
 pub struct ConsoleOptions {
   show_timestamp: bool,
   show_level: bool,
   show_context: bool,
+  theme: Theme,
+  formatter: Option<Box<dyn Formatter + Send>>,
+  stream: Stream,
+  color: ColorMode,
+}
+
+impl Default for ConsoleOptions {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl ConsoleOptions {
@@ -20,6 +145,10 @@ impl ConsoleOptions {
       show_timestamp: false,
       show_level: false,
       show_context: false,
+      theme: Theme::dark(),
+      formatter: None,
+      stream: Stream::Stdout,
+      color: ColorMode::Auto,
     }
   }
 
@@ -41,78 +170,159 @@ impl ConsoleOptions {
     self
   }
 
-  /// Create a `Console` instance from the options set.
+  /// Set the colour theme. Defaults to
+  /// [`Theme::dark`](../formatters/struct.Theme.html#method.dark).
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.theme = theme;
+    self
+  }
+
+  /// Set the formatter used to write each line.
+  ///
+  /// The formatter should write colour whenever it can. It is removed from
+  /// lines written to a stream colour is disabled for.
+  pub fn formatter<F>(mut self, formatter: F) -> Self
+  where
+    F: Formatter + Send + 'static,
+  {
+    self.formatter = Some(Box::new(formatter));
+    self
+  }
+
+  /// Set the stream messages are written to.
+  pub fn stream(mut self, stream: Stream) -> Self {
+    self.stream = stream;
+    self
+  }
+
+  /// Set when colour is written.
+  pub fn color(mut self, color: ColorMode) -> Self {
+    self.color = color;
+    self
+  }
+
+  /// Create a `Console` instance from the options set. In
+  /// [`ColorMode::Auto`](enum.ColorMode.html#variant.Auto) the streams and
+  /// environment are checked once, here.
   pub fn build(self) -> Console {
+    let formatter = match self.formatter {
+      Some(formatter) => formatter,
+      None => Box::new(
+        Text::new()
+          .show_timestamp(self.show_timestamp)
+          .show_level(self.show_level)
+          .show_context(self.show_context)
+          .colored(true)
+          .theme(self.theme),
+      ),
+    };
+    let stdout_color = self.color.enabled_for(&io::stdout());
+    let stderr_color = self.color.enabled_for(&io::stderr());
     Console {
-      show_timestamp: self.show_timestamp,
-      show_level: self.show_level,
-      show_context: self.show_context,
+      formatter,
+      stream: self.stream,
+      stdout: WriterOptions::new().strip_color(!stdout_color).build(io::stdout()),
+      stderr: WriterOptions::new().strip_color(!stderr_color).build(io::stderr()),
     }
   }
 }
 
-/// Console is a logger target which logs human readable output to stdout
+/// Console is a logger target which logs human readable output to stdout,
+/// stderr, or both. Each stream is written through a
+/// [`Writer`](struct.Writer.html) which flushes every line.
+///
+/// The `error_chain` and `backtrace` context values added by
+/// [`Logger::error_with`](../struct.Logger.html#method.error_with) and the
+/// [panic hook](../fn.install_panic_hook.html) are printed indented under the
+/// message, rather than on the same line. Values under those keys from any
+/// other context are printed with the rest of the context.
 pub struct Console {
-  show_timestamp: bool,
-  show_level: bool,
-  show_context: bool,
+  formatter: Box<dyn Formatter + Send>,
+  stream: Stream,
+  stdout: Writer<io::Stdout>,
+  stderr: Writer<io::Stderr>,
 }
 
 /// Console target
 impl Console {
   /// Create a new `Console` with default options.
-  /// This means timestamps levels and message contexts will be enabled.
+  /// This means timestamps levels and message contexts will be enabled,
+  /// messages are written to stdout, and colour is written if stdout is a
+  /// terminal.
   /// If you wish to configure these options then it's recomended to use
   /// `ConsoleOptions` to build your console instance.
   pub fn new() -> Self {
-    Self {
-      show_timestamp: true,
-      show_level: true,
-      show_context: true,
-    }
+    ConsoleOptions::new()
+      .formatter(Text::new().colored(true))
+      .build()
+  }
+}
+
+impl Default for Console {
+  fn default() -> Self {
+    Self::new()
   }
 }
 
 impl Target for Console {
-  fn log(&mut self, level: Level, message: &str, context: &Context) {
-    let mut message = message.to_string();
-
-    let mut context_pairs: Vec<_> = context.iter().collect();
-    context_pairs.sort_by(|a, b| a.0.cmp(b.0));
-
-    if self.show_context {
-      message += " ";
-      message += &context_pairs
-        .into_iter()
-        .map(|(key, val)| format!("{}={}", key, val))
-        .collect::<Vec<String>>()
-        .join(" ");
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    let line = match details(record) {
+      Some((context, details)) => {
+        let record = Record {
+          context: &context,
+          ..record.clone()
+        };
+        self.formatter.format(&record) + &details
+      }
+      None => self.formatter.format(record),
+    };
+    if self.stream.uses_stderr(record.level()) {
+      self.stderr.write_line(&line)
+    } else {
+      self.stdout.write_line(&line)
     }
+  }
 
-    if self.show_level {
-      let level_str = level.to_string();
-      let pad = " ".repeat(7 - level_str.chars().count());
-
-      let mut level_str = match level {
-        Level::Error => format!("{}", level_str).red().to_string(),
-        Level::Warn => format!("{}", level_str).yellow().to_string(),
-        Level::Info => format!("{}", level_str).white().to_string(),
-        Level::Verbose => format!("{}", level_str).cyan().to_string(),
-        Level::Debug => format!("{}", level_str).white().dimmed().to_string(),
-        Level::Trace => format!("{}", level_str).white().dimmed().to_string(),
-      };
+  fn flush(&mut self) -> Result<(), Error> {
+    self.stdout.flush()?;
+    self.stderr.flush()
+  }
+}
 
-      level_str += ":";
-      level_str += &pad;
+// Takes the error chain and backtrace added by `error_with` or the panic
+// hook out of a record's context, returning the rest of the context and the
+// lines to print under the message. Keys of the same name set by the caller
+// are left in the context.
+fn details(record: &Record) -> Option<(Context, String)> {
+  if !record.error_details {
+    return None;
+  }
+  let context = record.context();
+  let chain = match context.get("error_chain") {
+    Some(Value::Array(chain)) => Some(chain),
+    _ => None,
+  };
+  let backtrace = context.get("backtrace").and_then(Value::as_str);
+  if chain.is_none() && backtrace.is_none() {
+    return None;
+  }
 
-      message = format!("{} {}", level_str, message);
+  let mut rest = context.clone();
+  let mut details = String::new();
+  if let Some(chain) = chain {
+    rest.set("error_chain", "");
+    for error in chain {
+      details += "\n  caused by: ";
+      details += &error.to_string();
     }
-
-    if self.show_timestamp {
-      let timestamp = Local::now().to_rfc2822();
-      message = format!("{} - {}", timestamp, message);
+  }
+  if let Some(backtrace) = backtrace {
+    rest.set("backtrace", "");
+    details += "\n  backtrace:";
+    for line in backtrace.lines() {
+      details += "\n  ";
+      details += line;
     }
-
-    println!("{}", message);
   }
+  Some((rest, details))
 }
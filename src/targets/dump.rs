@@ -1,27 +1,68 @@
+use std::sync::{Arc, Mutex};
 use super::super::Context;
+use super::super::Error;
 use super::super::Level;
+use super::super::Record;
 use super::Target;
 
+/// A target which keeps every message logged to it in memory, for use in
+/// tests.
+///
+/// Clones of a `Dump` share the same messages, so a clone can be given to a
+/// logger and the original used to read what was logged.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::targets::Dump;
+///
+/// let dump = Dump::new();
+/// let logger = Logger::new(dump.clone(), context!{ src: "db" });
+/// logger.warn("slow query");
+///
+/// let messages = dump.dump_messages();
+/// assert_eq!(messages.len(), 1);
+/// assert_eq!(messages[0].0, Warn);
+/// assert_eq!(messages[0].1, "slow query");
+/// assert_eq!(messages[0].2.get("src").unwrap(), "db");
+/// assert!(dump.dump_messages().is_empty());
+/// # }
+/// ```
+#[derive(Clone)]
 pub struct Dump {
-  messages: Vec<(Level, String, Context)>,
+  messages: Arc<Mutex<Vec<(Level, String, Context)>>>,
 }
 
 impl Dump {
+  /// Creates a new empty `Dump`.
   pub fn new() -> Self {
     Self {
-      messages: Vec::new(),
+      messages: Arc::new(Mutex::new(Vec::new())),
     }
   }
 
-  pub fn dump_messages(&mut self) -> Vec<(Level, String, Context)> {
-    self.messages.drain(..).collect()
+  /// Removes and returns every message logged so far.
+  pub fn dump_messages(&self) -> Vec<(Level, String, Context)> {
+    self.messages.lock().unwrap().drain(..).collect()
+  }
+}
+
+impl Default for Dump {
+  fn default() -> Self {
+    Self::new()
   }
 }
 
 impl Target for Dump {
-  fn log(&mut self, level: Level, message: &str, context: &Context) {
-    self
-      .messages
-      .push((level, message.to_string(), context.clone()));
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    self.messages.lock().unwrap().push((
+      record.level(),
+      record.message().to_string(),
+      record.context().clone(),
+    ));
+    Ok(())
   }
 }
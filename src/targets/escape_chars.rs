@@ -1,4 +1,9 @@
-
+/// Escapes each of `chars` in `i` with a backslash, unless already escaped.
+///
+/// This is not enough to produce valid JSON, and no target uses it since
+/// JSON escaping moved into the [`Json`](../formatters/struct.Json.html)
+/// formatter.
+#[deprecated(note = "use `quil::formatters::escape_json` to escape JSON strings")]
 pub fn escape_chars(i: &str, chars: &str) -> String {
   let mut o = String::new();
   let mut esc = false;
@@ -1,49 +1,177 @@
 use std::fs::{File, OpenOptions};
-use std::io::{Error, Write};
-use std::path::PathBuf;
-use super::super::Context;
-use super::super::Level;
-use super::Target;
-use super::escape_chars;
+use std::io;
+use std::path::{Path, PathBuf};
+use super::super::Error;
+use super::super::Record;
+use super::super::formatters::{Json, Precision, Timezone};
+use super::{Target, Writer, WriterOptions};
+
+/// Contains configuration options for a `JsonFile` object.
+///
+/// By default each record carries a UTC timestamp with millisecond
+/// precision, the `hostname`, `pid` and `thread` fields are left out, and
+/// each line is flushed to the file as soon as it is written.
+pub struct JsonFileOptions {
+  path: PathBuf,
+  json: Json,
+  writer: WriterOptions,
+}
+
+impl JsonFileOptions {
+  /// Creates a new instance of `JsonFileOptions`.
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - The path of the log file. Lines are appended if it exists.
+  pub fn new<P>(path: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    Self {
+      path: path.into(),
+      json: Json::new(),
+      writer: WriterOptions::new(),
+    }
+  }
+
+  /// Enable/disable timestamps.
+  pub fn show_timestamp(mut self, show_timestamp: bool) -> Self {
+    self.json = self.json.show_timestamp(show_timestamp);
+    self
+  }
+
+  /// Set the number of fractional second digits in timestamps.
+  pub fn precision(mut self, precision: Precision) -> Self {
+    self.json = self.json.precision(precision);
+    self
+  }
+
+  /// Set the timezone timestamps are written in.
+  pub fn timezone(mut self, timezone: Timezone) -> Self {
+    self.json = self.json.timezone(timezone);
+    self
+  }
+
+  /// Enable/disable the `hostname` field.
+  pub fn show_hostname(mut self, show_hostname: bool) -> Self {
+    self.json = self.json.show_hostname(show_hostname);
+    self
+  }
+
+  /// Enable/disable the `pid` field.
+  pub fn show_pid(mut self, show_pid: bool) -> Self {
+    self.json = self.json.show_pid(show_pid);
+    self
+  }
+
+  /// Enable/disable the `thread` field.
+  pub fn show_thread(mut self, show_thread: bool) -> Self {
+    self.json = self.json.show_thread(show_thread);
+    self
+  }
+
+  /// Set the size of the buffer lines are collected in before being written
+  /// to the file. See [`WriterOptions`](struct.WriterOptions.html).
+  pub fn buffer_capacity(mut self, buffer_capacity: usize) -> Self {
+    self.writer = self.writer.buffer_capacity(buffer_capacity);
+    self
+  }
+
+  /// Enable/disable flushing after every line. See
+  /// [`WriterOptions`](struct.WriterOptions.html).
+  pub fn line_flush(mut self, line_flush: bool) -> Self {
+    self.writer = self.writer.line_flush(line_flush);
+    self
+  }
+
+  /// Create a `JsonFile` instance from the options set, opening the log
+  /// file.
+  pub fn build(self) -> Result<JsonFile, io::Error> {
+    let file = open(&self.path)?;
+    Ok(JsonFile {
+      writer: self.writer.formatter(self.json).build(file),
+      path: self.path,
+    })
+  }
+}
 
 /// Line separated JSON log file target
+///
+/// This is a [`Writer`](struct.Writer.html) to a file using the
+/// [`Json`](../formatters/struct.Json.html) formatter.
+///
+/// Each line is a JSON object with an RFC 3339 `timestamp`, the `level`, the
+/// `message` and the `context`. Context values are written in their native
+/// JSON form, so numbers and booleans are not quoted. Each line is strictly
+/// valid JSON, with control characters in messages, keys and values
+/// escaped.
+///
+/// After an external tool such as logrotate renames the file,
+/// [`Logger::reopen`](../struct.Logger.html#method.reopen) starts a new file
+/// at the original path.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use std::fs;
+/// use quil::formatters::{Precision, Timezone};
+/// use quil::targets::JsonFileOptions;
+///
+/// # let path = std::env::temp_dir().join("quil_json_file_example.json");
+/// # let _ = fs::remove_file(&path);
+/// let target = JsonFileOptions::new(path.clone())
+///   .precision(Precision::Micros)
+///   .timezone(Timezone::Local)
+///   .show_pid(true)
+///   .build()
+///   .unwrap();
+///
+/// let logger = Logger::new(target, context!{ retries: 3 });
+/// logger.info("hello");
+///
+/// let line = fs::read_to_string(&path).unwrap();
+/// assert!(line.starts_with("{ \"timestamp\": \""));
+/// assert!(line.ends_with(&format!(
+///   "\"level\": \"info\", \"message\": \"hello\", \"pid\": {}, \"context\": {{ \"retries\": 3 }} }}\n",
+///   std::process::id()
+/// )));
+/// # }
+/// ```
 pub struct JsonFile {
-  file: File,
+  writer: Writer<File>,
+  path: PathBuf,
 }
 
 impl JsonFile {
-  pub fn open<P>(path: P) -> Result<Self, Error>
+  /// Opens a `JsonFile` with default options, appending to the file if it
+  /// exists.
+  pub fn open<P>(path: P) -> Result<Self, io::Error>
   where
     P: Into<PathBuf>,
   {
-    Ok(Self {
-      file: OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path.into())?,
-    })
+    JsonFileOptions::new(path).build()
   }
 }
 
 impl Target for JsonFile {
-  fn log(&mut self, level: Level, message: &str, context: &Context) {
-    let message = escape_chars(&message, "\"\\");
-    let context = context
-      .iter()
-      .map(|(key, val)| {
-        let key = escape_chars(&key, "\"\\");
-        let val = escape_chars(&val, "\"\\");
-        format!("\"{}\": \"{}\"", key, val)
-      })
-      .collect::<Vec<String>>()
-      .join(", ");
-
-    write!(
-      self.file,
-      "{{ \"level\": \"{}\", \"message\": \"{}\", \"context\": {{ {} }} }}\n",
-      level,
-      message,
-      context,
-    ).unwrap();
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    self.writer.log(record)
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.writer.flush()
   }
+
+  fn reopen(&mut self) -> Result<(), Error> {
+    let file = open(&self.path)?;
+    self.writer.replace(file)?;
+    Ok(())
+  }
+}
+
+fn open(path: &Path) -> Result<File, io::Error> {
+  OpenOptions::new().create(true).append(true).open(path)
 }
@@ -0,0 +1,150 @@
+use std::io::{self, Write};
+use super::super::Error;
+use super::super::Record;
+use super::super::formatters::{self, Precision, Timezone};
+use super::{Target, Writer, WriterOptions};
+
+/// Contains configuration options for a `Logfmt` object.
+///
+/// By default each line carries a UTC timestamp with millisecond precision
+/// and is flushed as soon as it is written.
+pub struct LogfmtOptions {
+  logfmt: formatters::Logfmt,
+  writer: WriterOptions,
+}
+
+impl LogfmtOptions {
+  /// Creates a new instance of `LogfmtOptions`.
+  pub fn new() -> Self {
+    Self {
+      logfmt: formatters::Logfmt::new(),
+      writer: WriterOptions::new(),
+    }
+  }
+
+  /// Enable/disable the `ts` field.
+  pub fn show_timestamp(mut self, show_timestamp: bool) -> Self {
+    self.logfmt = self.logfmt.show_timestamp(show_timestamp);
+    self
+  }
+
+  /// Set the number of fractional second digits in timestamps.
+  pub fn precision(mut self, precision: Precision) -> Self {
+    self.logfmt = self.logfmt.precision(precision);
+    self
+  }
+
+  /// Set the timezone timestamps are written in.
+  pub fn timezone(mut self, timezone: Timezone) -> Self {
+    self.logfmt = self.logfmt.timezone(timezone);
+    self
+  }
+
+  /// Set the size of the buffer lines are collected in before being written.
+  /// See [`WriterOptions`](struct.WriterOptions.html).
+  pub fn buffer_capacity(mut self, buffer_capacity: usize) -> Self {
+    self.writer = self.writer.buffer_capacity(buffer_capacity);
+    self
+  }
+
+  /// Enable/disable flushing after every line. See
+  /// [`WriterOptions`](struct.WriterOptions.html).
+  pub fn line_flush(mut self, line_flush: bool) -> Self {
+    self.writer = self.writer.line_flush(line_flush);
+    self
+  }
+
+  /// Create a `Logfmt` instance writing to the given writer from the
+  /// options set.
+  ///
+  /// # Arguments
+  ///
+  /// * `writer` - Where lines are written, such as `io::stdout()` or a file.
+  pub fn build<W>(self, writer: W) -> Logfmt<W>
+  where
+    W: Write + Send,
+  {
+    Logfmt {
+      writer: self.writer.formatter(self.logfmt).build(writer),
+    }
+  }
+}
+
+impl Default for LogfmtOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A target which writes logfmt lines to any `io::Write`. This is a
+/// [`Writer`](struct.Writer.html) using the
+/// [`Logfmt`](../formatters/struct.Logfmt.html) formatter, which describes
+/// the format.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use std::fs;
+/// use quil::targets::{Logfmt, LogfmtOptions};
+///
+/// let stdout = Logfmt::new(std::io::stdout());
+///
+/// # let path = std::env::temp_dir().join("quil_logfmt_example.log");
+/// # let _ = fs::remove_file(&path);
+/// let file = LogfmtOptions::new()
+///   .show_timestamp(false)
+///   .build(fs::File::create(&path).unwrap());
+///
+/// let logger = Logger::new(targets![stdout, file], context!{ src: "db" });
+/// logger.warn("slow query");
+///
+/// assert_eq!(fs::read_to_string(&path).unwrap(), "level=warn msg=\"slow query\" src=db\n");
+/// # }
+/// ```
+pub struct Logfmt<W>
+where
+  W: Write + Send,
+{
+  writer: Writer<W>,
+}
+
+impl<W> Logfmt<W>
+where
+  W: Write + Send,
+{
+  /// Creates a new `Logfmt` with default options writing to the given
+  /// writer.
+  ///
+  /// # Arguments
+  ///
+  /// * `writer` - Where lines are written, such as `io::stdout()` or a file.
+  pub fn new(writer: W) -> Self {
+    LogfmtOptions::new().build(writer)
+  }
+
+  /// Returns a reference to the underlying writer.
+  pub fn get_ref(&self) -> &W {
+    self.writer.get_ref()
+  }
+
+  /// Flushes any buffered lines and returns the underlying writer.
+  pub fn into_inner(self) -> Result<W, io::Error> {
+    self.writer.into_inner()
+  }
+}
+
+impl<W> Target for Logfmt<W>
+where
+  W: Write + Send,
+{
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    self.writer.log(record)
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.writer.flush()
+  }
+}
@@ -1,17 +1,30 @@
 //! Contains logger targets and the target trait for implementing custom
 //! targets
 
+mod async_target;
 mod console;
 mod dump;
 mod escape_chars;
 mod json_file;
+mod logfmt;
+mod rolling_file;
+mod syslog;
 #[macro_use]
 mod target_set;
 mod target;
+mod writer;
 
-pub use self::console::{Console, ConsoleOptions};
+pub use self::async_target::{
+  AsyncGuard, AsyncHandle, AsyncTarget, AsyncTargetOptions, Overflow,
+};
+pub use self::console::{ColorMode, Console, ConsoleOptions, Stream};
 pub use self::dump::Dump;
+#[allow(deprecated)]
 pub use self::escape_chars::escape_chars;
-pub use self::json_file::JsonFile;
+pub use self::json_file::{JsonFile, JsonFileOptions};
+pub use self::logfmt::{Logfmt, LogfmtOptions};
+pub use self::rolling_file::{Period, RollingFile, RollingFileOptions};
+pub use self::syslog::{Facility, Framing, Syslog, SyslogFormat, SyslogOptions, Transport};
 pub use self::target_set::TargetSet;
-pub use self::target::Target;
+pub use self::target::{BasicTarget, Target};
+pub use self::writer::{Writer, WriterOptions};
@@ -0,0 +1,308 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local};
+use super::super::Error;
+use super::super::Record;
+use super::super::formatters::{Formatter, Json};
+use super::Target;
+
+const ARCHIVE_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%6f";
+
+/// The time boundary on which a [`RollingFile`](struct.RollingFile.html)
+/// rotates. Boundaries are in local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+  Hourly,
+  Daily,
+}
+
+impl Period {
+  fn key(self, time: &DateTime<Local>) -> String {
+    match self {
+      Period::Hourly => time.format("%Y%m%d%H").to_string(),
+      Period::Daily => time.format("%Y%m%d").to_string(),
+    }
+  }
+}
+
+/// Contains configuration options for a `RollingFile` object.
+///
+/// By default the file never rotates, every archive is kept, and lines are
+/// written in the [`Json`](../formatters/struct.Json.html) format used by
+/// [`JsonFile`](struct.JsonFile.html).
+pub struct RollingFileOptions {
+  path: PathBuf,
+  max_size: Option<u64>,
+  period: Option<Period>,
+  keep: Option<usize>,
+  formatter: Box<dyn Formatter + Send>,
+}
+
+impl RollingFileOptions {
+  /// Creates a new instance of `RollingFileOptions`.
+  ///
+  /// # Arguments
+  ///
+  /// * `path` - The path of the active log file. Archives are written
+  ///   next to it.
+  pub fn new<P>(path: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    Self {
+      path: path.into(),
+      max_size: None,
+      period: None,
+      keep: None,
+      formatter: Box::new(Json::new()),
+    }
+  }
+
+  /// Rotate once writing a line would grow the file beyond `max_size` bytes.
+  pub fn max_size(mut self, max_size: u64) -> Self {
+    self.max_size = Some(max_size);
+    self
+  }
+
+  /// Rotate when the first line of a new hour or day is written.
+  pub fn period(mut self, period: Period) -> Self {
+    self.period = Some(period);
+    self
+  }
+
+  /// Keep only the newest `keep` archives, deleting older ones on rotation.
+  pub fn keep(mut self, keep: usize) -> Self {
+    self.keep = Some(keep);
+    self
+  }
+
+  /// Set the formatter used to write each line.
+  pub fn formatter<F>(mut self, formatter: F) -> Self
+  where
+    F: Formatter + Send + 'static,
+  {
+    self.formatter = Box::new(formatter);
+    self
+  }
+
+  /// Create a `RollingFile` instance from the options set, opening the
+  /// active log file.
+  pub fn build(self) -> Result<RollingFile, io::Error> {
+    let file = open(&self.path)?;
+    let metadata = file.metadata()?;
+    let modified: DateTime<Local> = match metadata.modified() {
+      Ok(modified) if metadata.len() > 0 => modified.into(),
+      _ => Local::now(),
+    };
+
+    Ok(RollingFile {
+      size: metadata.len(),
+      period_key: self.period.map(|period| period.key(&modified)),
+      last_write: modified,
+      path: self.path,
+      file,
+      max_size: self.max_size,
+      period: self.period,
+      keep: self.keep,
+      formatter: self.formatter,
+    })
+  }
+}
+
+/// Line separated log file target which rotates the file by size, by time,
+/// or both.
+///
+/// When the file rotates it is renamed to an archive with the time of the
+/// last line written to it inserted before its extension, for example
+/// `app.json` becomes `app.20171107-235542-000000.json`, and a new file is
+/// opened in its place. Files rotated at the start of a new hour or day are
+/// therefore named after the period they hold. If an archive with the same
+/// name already exists a counter is appended, as in
+/// `app.20171107-235542-000000-1.json`, so no archive is overwritten.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use std::fs;
+/// use quil::formatters::Text;
+/// use quil::targets::{Period, RollingFileOptions};
+///
+/// # let dir = std::env::temp_dir().join("quil_rolling_file_example");
+/// # let _ = fs::remove_dir_all(&dir);
+/// # fs::create_dir_all(&dir).unwrap();
+/// let target = RollingFileOptions::new(dir.join("app.log"))
+///   .max_size(64)
+///   .period(Period::Daily)
+///   .keep(2)
+///   .formatter(Text::new().show_timestamp(false))
+///   .build()
+///   .unwrap();
+///
+/// let logger = Logger::new(target, context!{ src: "root" });
+/// for i in 0..10 {
+///   logger.info(&format!("message {}", i));
+/// }
+///
+/// // The active file plus the two newest archives.
+/// assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+/// # }
+/// ```
+pub struct RollingFile {
+  path: PathBuf,
+  file: File,
+  size: u64,
+  period_key: Option<String>,
+  last_write: DateTime<Local>,
+  max_size: Option<u64>,
+  period: Option<Period>,
+  keep: Option<usize>,
+  formatter: Box<dyn Formatter + Send>,
+}
+
+impl RollingFile {
+  /// Creates a new `RollingFile` which writes JSON lines and rotates once
+  /// the file would grow beyond `max_size` bytes. Use `RollingFileOptions`
+  /// for time based rotation, retention and other formats.
+  pub fn open<P>(path: P, max_size: u64) -> Result<Self, io::Error>
+  where
+    P: Into<PathBuf>,
+  {
+    RollingFileOptions::new(path).max_size(max_size).build()
+  }
+
+  fn should_rotate(&self, now: &DateTime<Local>, line_len: u64) -> bool {
+    if let Some(max_size) = self.max_size {
+      if self.size > 0 && self.size + line_len > max_size {
+        return true;
+      }
+    }
+    match (self.period, &self.period_key) {
+      (Some(period), Some(key)) => period.key(now) != *key,
+      _ => false,
+    }
+  }
+
+  fn rotate(&mut self, now: &DateTime<Local>) -> Result<(), io::Error> {
+    self.file.flush()?;
+
+    let (stem, extension) = split_file_name(&self.path);
+    let timestamp = self.last_write.format(ARCHIVE_TIMESTAMP_FORMAT).to_string();
+    let mut archive = self
+      .path
+      .with_file_name(format!("{}.{}{}", stem, timestamp, extension));
+    let mut counter = 0;
+    while archive.exists() {
+      counter += 1;
+      archive = self
+        .path
+        .with_file_name(format!("{}.{}-{}{}", stem, timestamp, counter, extension));
+    }
+    fs::rename(&self.path, archive)?;
+
+    self.file = open(&self.path)?;
+    self.size = 0;
+    self.period_key = self.period.map(|period| period.key(now));
+
+    if let Some(keep) = self.keep {
+      self.remove_old_archives(&stem, &extension, keep)?;
+    }
+    Ok(())
+  }
+
+  fn remove_old_archives(
+    &self,
+    stem: &str,
+    extension: &str,
+    keep: usize,
+  ) -> Result<(), io::Error> {
+    let dir = match self.path.parent() {
+      Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+      _ => PathBuf::from("."),
+    };
+    let prefix = format!("{}.", stem);
+
+    let mut archives: Vec<_> = fs::read_dir(&dir)?
+      .filter_map(|entry| entry.ok())
+      .filter_map(|entry| entry.file_name().into_string().ok())
+      .filter(|name| {
+        name.len() > prefix.len() + extension.len()
+          && name.starts_with(&prefix)
+          && name.ends_with(extension)
+          && name[prefix.len()..name.len() - extension.len()]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-')
+      })
+      .collect();
+    archives.sort_by_key(|name| archive_order(&name[prefix.len()..name.len() - extension.len()]));
+
+    let excess = archives.len().saturating_sub(keep);
+    for name in archives.into_iter().take(excess) {
+      fs::remove_file(dir.join(name))?;
+    }
+    Ok(())
+  }
+}
+
+impl Target for RollingFile {
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    let line = self.formatter.format(record) + "\n";
+    let now = record.timestamp().with_timezone(&Local);
+
+    if self.should_rotate(&now, line.len() as u64) {
+      self.rotate(&now)?;
+    }
+
+    self.file.write_all(line.as_bytes())?;
+    self.size += line.len() as u64;
+    self.last_write = now;
+    Ok(())
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.file.flush()?;
+    Ok(())
+  }
+
+  fn reopen(&mut self) -> Result<(), Error> {
+    let file = open(&self.path)?;
+    let size = file.metadata()?.len();
+    self.file.flush()?;
+    self.file = file;
+    self.size = size;
+    if size == 0 {
+      let now = Local::now();
+      self.period_key = self.period.map(|period| period.key(&now));
+      self.last_write = now;
+    }
+    Ok(())
+  }
+}
+
+fn open(path: &Path) -> Result<File, io::Error> {
+  OpenOptions::new().create(true).append(true).open(path)
+}
+
+// Orders archive names by their timestamp, then by the counter appended to
+// archives sharing a timestamp.
+fn archive_order(name: &str) -> (String, u32) {
+  match name.match_indices('-').nth(2) {
+    Some((index, _)) => (name[..index].to_string(), name[index + 1..].parse().unwrap_or(0)),
+    None => (name.to_string(), 0),
+  }
+}
+
+fn split_file_name(path: &Path) -> (String, String) {
+  let stem = path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let extension = path
+    .extension()
+    .map(|extension| format!(".{}", extension.to_string_lossy()))
+    .unwrap_or_default();
+  (stem, extension)
+}
@@ -0,0 +1,492 @@
+use std::env;
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::process;
+use chrono::{DateTime, Local, SecondsFormat};
+use hostname;
+use super::super::Context;
+use super::super::Error;
+use super::super::Level;
+use super::super::Record;
+use super::super::Value;
+use super::Target;
+
+/// Syslog facilities as defined by RFC 5424.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facility {
+  Kern = 0,
+  User = 1,
+  Mail = 2,
+  Daemon = 3,
+  Auth = 4,
+  Syslog = 5,
+  Lpr = 6,
+  News = 7,
+  Uucp = 8,
+  Cron = 9,
+  Authpriv = 10,
+  Ftp = 11,
+  Local0 = 16,
+  Local1 = 17,
+  Local2 = 18,
+  Local3 = 19,
+  Local4 = 20,
+  Local5 = 21,
+  Local6 = 22,
+  Local7 = 23,
+}
+
+/// The syslog message format written by a [`Syslog`](struct.Syslog.html)
+/// target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFormat {
+  /// The IETF syslog protocol. Contexts are written as structured data if
+  /// an SD-ID is set with
+  /// [`SyslogOptions::sd_id`](struct.SyslogOptions.html#method.sd_id), and
+  /// appended to the message as `key=value` pairs otherwise.
+  Rfc5424,
+  /// The legacy BSD syslog protocol. Contexts are appended to the message as
+  /// `key=value` pairs.
+  Rfc3164,
+}
+
+/// The socket a [`Syslog`](struct.Syslog.html) target sends messages over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+  /// A local unix datagram socket such as `/dev/log`.
+  Unix(PathBuf),
+  /// A remote syslog server over UDP.
+  Udp(SocketAddr),
+  /// A remote syslog server over TCP. Messages are framed as set by
+  /// [`SyslogOptions::framing`](struct.SyslogOptions.html#method.framing).
+  Tcp(SocketAddr),
+}
+
+/// How messages sent over [`Transport::Tcp`](enum.Transport.html#variant.Tcp)
+/// are delimited, as described by RFC 6587.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+  /// Each message is prefixed with its length in bytes and a space.
+  OctetCounting,
+  /// Each message is followed by a newline. Newlines in messages are
+  /// replaced with spaces.
+  Newline,
+}
+
+/// Contains configuration options for a `Syslog` object.
+///
+/// By default messages are sent to `/dev/log` in the RFC 5424 format with
+/// the `User` facility, the name of the current executable as the app name,
+/// and the current process id as the procid. Messages sent over TCP use
+/// octet counting. No SD-ID is set, so contexts are appended to the message
+/// until one is.
+pub struct SyslogOptions {
+  transport: Transport,
+  framing: Framing,
+  format: SyslogFormat,
+  facility: Facility,
+  hostname: Option<String>,
+  app_name: Option<String>,
+  procid: Option<String>,
+  sd_id: Option<String>,
+}
+
+impl SyslogOptions {
+  /// Creates a new instance of `SyslogOptions`.
+  pub fn new() -> Self {
+    Self {
+      transport: Transport::Unix(PathBuf::from("/dev/log")),
+      framing: Framing::OctetCounting,
+      format: SyslogFormat::Rfc5424,
+      facility: Facility::User,
+      hostname: hostname::get()
+        .ok()
+        .and_then(|hostname| hostname.into_string().ok()),
+      app_name: env::current_exe().ok().and_then(|path| {
+        path
+          .file_name()
+          .and_then(|name| name.to_str())
+          .map(|name| name.to_string())
+      }),
+      procid: Some(process::id().to_string()),
+      sd_id: None,
+    }
+  }
+
+  /// Set the socket messages are sent over.
+  pub fn transport(mut self, transport: Transport) -> Self {
+    self.transport = transport;
+    self
+  }
+
+  /// Set how messages sent over TCP are delimited.
+  pub fn framing(mut self, framing: Framing) -> Self {
+    self.framing = framing;
+    self
+  }
+
+  /// Set the message format.
+  pub fn format(mut self, format: SyslogFormat) -> Self {
+    self.format = format;
+    self
+  }
+
+  /// Set the facility messages are logged under.
+  pub fn facility(mut self, facility: Facility) -> Self {
+    self.facility = facility;
+    self
+  }
+
+  /// Set the hostname sent with each message.
+  pub fn hostname<S>(mut self, hostname: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.hostname = Some(hostname.into());
+    self
+  }
+
+  /// Set the app name sent with each message.
+  pub fn app_name<S>(mut self, app_name: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.app_name = Some(app_name.into());
+    self
+  }
+
+  /// Set the procid sent with each message.
+  pub fn procid<S>(mut self, procid: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.procid = Some(procid.into());
+    self
+  }
+
+  /// Set the SD-ID of the structured data element contexts are written to
+  /// in the RFC 5424 format, such as `myapp@12345`.
+  ///
+  /// There is no default. RFC 5424 reserves SD-IDs without an `@` for
+  /// names registered with IANA, and a private SD-ID must carry the private
+  /// enterprise number of the organisation which defines it, which only
+  /// the application can know. Until one is set, contexts are appended to
+  /// the message instead.
+  pub fn sd_id<S>(mut self, sd_id: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.sd_id = Some(sd_id.into());
+    self
+  }
+
+  /// Create a `Syslog` instance from the options set, connecting to the
+  /// transport's socket.
+  pub fn build(self) -> Result<Syslog, io::Error> {
+    let connection = Connection::open(&self.transport)?;
+    Ok(Syslog {
+      transport: self.transport,
+      connection,
+      framing: self.framing,
+      format: self.format,
+      facility: self.facility,
+      hostname: header_field(self.hostname, 255),
+      app_name: header_field(self.app_name, 48),
+      procid: header_field(self.procid, 128),
+      sd_id: self.sd_id.as_ref().map(|sd_id| sd_name(sd_id)),
+    })
+  }
+}
+
+impl Default for SyslogOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Syslog is a logger target which sends messages to a syslog server.
+///
+/// Levels are mapped to syslog severities as follows. `Error` is sent as
+/// `err`, `Warn` as `warning`, `Info` and `Verbose` as `info`, and `Debug`
+/// and `Trace` as `debug`.
+///
+/// # Examples
+///
+/// Sending to a local syslog server over UDP:
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use std::net::UdpSocket;
+/// use quil::targets::{Facility, SyslogOptions, Transport};
+///
+/// let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+///
+/// let syslog = SyslogOptions::new()
+///   .transport(Transport::Udp(server.local_addr().unwrap()))
+///   .facility(Facility::Local0)
+///   .hostname("web1")
+///   .app_name("api")
+///   .procid("42")
+///   // 32473 is the enterprise number RFC 5612 sets aside for examples.
+///   .sd_id("api@32473")
+///   .build()
+///   .unwrap();
+/// let logger = Logger::new(syslog, context!{ src: "root" });
+/// logger.warn("disk full");
+///
+/// let mut buf = [0; 1024];
+/// let len = server.recv(&mut buf).unwrap();
+/// let message = String::from_utf8_lossy(&buf[..len]);
+///
+/// assert!(message.starts_with("<132>1 "));
+/// assert!(message.ends_with(" web1 api 42 - [api@32473 src=\"root\"] disk full"));
+/// # }
+/// ```
+///
+/// Sending to a syslog server over TCP with octet counting:
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use std::io::Read;
+/// use std::net::TcpListener;
+/// use quil::targets::{SyslogFormat, SyslogOptions, Transport};
+///
+/// let server = TcpListener::bind("127.0.0.1:0").unwrap();
+///
+/// let syslog = SyslogOptions::new()
+///   .transport(Transport::Tcp(server.local_addr().unwrap()))
+///   .format(SyslogFormat::Rfc3164)
+///   .hostname("web1")
+///   .app_name("api")
+///   .procid("42")
+///   .build()
+///   .unwrap();
+/// let logger = Logger::new(syslog, context!{ src: "root" });
+/// logger.info("hello");
+/// drop(logger);
+///
+/// let mut received = String::new();
+/// server.accept().unwrap().0.read_to_string(&mut received).unwrap();
+///
+/// let (len, message) = received.split_at(received.find(' ').unwrap());
+/// let message = &message[1..];
+///
+/// assert_eq!(len.parse::<usize>().unwrap(), message.len());
+/// assert!(message.starts_with("<14>"));
+/// assert!(message.ends_with(" web1 api[42]: hello src=root"));
+/// # }
+/// ```
+pub struct Syslog {
+  transport: Transport,
+  connection: Connection,
+  framing: Framing,
+  format: SyslogFormat,
+  facility: Facility,
+  hostname: String,
+  app_name: String,
+  procid: String,
+  sd_id: Option<String>,
+}
+
+impl Syslog {
+  /// Create a new `Syslog` with default options sending to `/dev/log`. If
+  /// you wish to configure the target then it's recomended to use
+  /// `SyslogOptions` to build your syslog instance.
+  pub fn new() -> Result<Self, io::Error> {
+    SyslogOptions::new().build()
+  }
+
+  fn format_rfc5424(&self, priority: u8, timestamp: &DateTime<Local>, record: &Record) -> String {
+    let timestamp = timestamp.to_rfc3339_opts(SecondsFormat::Micros, false);
+    let (structured_data, pairs) = match self.sd_id {
+      Some(ref sd_id) => (structured_data(sd_id, record.context()), String::new()),
+      None => ("-".to_string(), context_pairs(record.context())),
+    };
+    format!(
+      "<{}>1 {} {} {} {} - {} {}{}",
+      priority,
+      timestamp,
+      self.hostname,
+      self.app_name,
+      self.procid,
+      structured_data,
+      record.message(),
+      pairs,
+    )
+  }
+
+  fn format_rfc3164(&self, priority: u8, timestamp: &DateTime<Local>, record: &Record) -> String {
+    let timestamp = timestamp.format("%b %e %H:%M:%S");
+    format!(
+      "<{}>{} {} {}[{}]: {}{}",
+      priority,
+      timestamp,
+      self.hostname,
+      self.app_name,
+      self.procid,
+      record.message(),
+      context_pairs(record.context()),
+    )
+  }
+
+  fn send(&mut self, message: &str) -> Result<(), io::Error> {
+    if let Err(err) = self.connection.send(message, self.framing) {
+      match self.connection {
+        Connection::Tcp(_) => {
+          self.connection = Connection::open(&self.transport)?;
+          self.connection.send(message, self.framing)?;
+        }
+        _ => return Err(err),
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Target for Syslog {
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    let priority = (self.facility as u8) * 8 + severity(record.level());
+    let timestamp = record.timestamp().with_timezone(&Local);
+    let message = match self.format {
+      SyslogFormat::Rfc5424 => self.format_rfc5424(priority, &timestamp, record),
+      SyslogFormat::Rfc3164 => self.format_rfc3164(priority, &timestamp, record),
+    };
+    self.send(&message)?;
+    Ok(())
+  }
+}
+
+enum Connection {
+  #[cfg(unix)]
+  Unix(UnixDatagram, PathBuf),
+  Udp(UdpSocket, SocketAddr),
+  Tcp(TcpStream),
+}
+
+impl Connection {
+  fn open(transport: &Transport) -> Result<Self, io::Error> {
+    match *transport {
+      #[cfg(unix)]
+      Transport::Unix(ref path) => {
+        Ok(Connection::Unix(UnixDatagram::unbound()?, path.clone()))
+      }
+      #[cfg(not(unix))]
+      Transport::Unix(_) => Err(io::Error::new(
+        io::ErrorKind::Other,
+        "unix sockets are not supported on this platform",
+      )),
+      Transport::Udp(addr) => {
+        let bind_addr = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        Ok(Connection::Udp(UdpSocket::bind(bind_addr)?, addr))
+      }
+      Transport::Tcp(addr) => Ok(Connection::Tcp(TcpStream::connect(addr)?)),
+    }
+  }
+
+  fn send(&mut self, message: &str, framing: Framing) -> Result<(), io::Error> {
+    match *self {
+      #[cfg(unix)]
+      Connection::Unix(ref socket, ref path) => {
+        socket.send_to(message.as_bytes(), path).map(|_| ())
+      }
+      Connection::Udp(ref socket, addr) => {
+        socket.send_to(message.as_bytes(), addr).map(|_| ())
+      }
+      Connection::Tcp(ref mut stream) => {
+        match framing {
+          Framing::OctetCounting => write!(stream, "{} {}", message.len(), message)?,
+          Framing::Newline => writeln!(stream, "{}", message.replace('\n', " "))?,
+        }
+        stream.flush()
+      }
+    }
+  }
+}
+
+fn severity(level: Level) -> u8 {
+  match level {
+    Level::Error => 3,
+    Level::Warn => 4,
+    Level::Info | Level::Verbose => 6,
+    Level::Debug | Level::Trace => 7,
+  }
+}
+
+fn structured_data(sd_id: &str, context: &Context) -> String {
+  if context.iter().next().is_none() {
+    return "-".to_string();
+  }
+  let params: String = sorted_pairs(context)
+    .into_iter()
+    .map(|(key, val)| {
+      format!(" {}=\"{}\"", sd_name(key), sd_value(&val.to_string()))
+    })
+    .collect();
+  format!("[{}{}]", sd_id, params)
+}
+
+// The context as ` key=value` pairs to append to a message.
+fn context_pairs(context: &Context) -> String {
+  sorted_pairs(context)
+    .into_iter()
+    .map(|(key, val)| format!(" {}={}", key, val))
+    .collect()
+}
+
+fn sorted_pairs(context: &Context) -> Vec<(&String, &Value)> {
+  let mut pairs: Vec<_> = context.iter().collect();
+  pairs.sort_by(|a, b| a.0.cmp(b.0));
+  pairs
+}
+
+// Header fields must be printable US-ASCII without spaces, or "-" if empty.
+fn header_field(value: Option<String>, max_len: usize) -> String {
+  let value: String = value
+    .unwrap_or_default()
+    .chars()
+    .filter(|c| c.is_ascii_graphic())
+    .take(max_len)
+    .collect();
+  if value.is_empty() {
+    "-".to_string()
+  } else {
+    value
+  }
+}
+
+// SD-IDs and parameter names may not contain '=', ' ', ']' or '"' and are
+// limited to 32 characters.
+fn sd_name(name: &str) -> String {
+  let name: String = name
+    .chars()
+    .map(|c| match c {
+      '=' | ']' | '"' => '_',
+      c if c.is_ascii_graphic() => c,
+      _ => '_',
+    })
+    .take(32)
+    .collect();
+  if name.is_empty() {
+    "_".to_string()
+  } else {
+    name
+  }
+}
+
+fn sd_value(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for c in value.chars() {
+    if c == '"' || c == '\\' || c == ']' {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
@@ -1,5 +1,7 @@
 use super::super::Context;
+use super::super::Error;
 use super::super::Level;
+use super::super::Record;
 
 /// Structs that implement `Target` can be used with
 /// [`Logger`](struct.Logger.html).
@@ -10,13 +12,21 @@ use super::super::Level;
 ///
 /// ```rust
 /// # use quil::prelude::*;
-/// # use quil::Level;
+/// # use quil::Error;
+/// # use quil::Record;
 /// # use quil::targets::Target;
 /// struct Print;
 ///
 /// impl Target for Print {
-///   fn log(&mut self, level: Level, message: &str, context: &Context) {
-///     println!("LEVEL: {}, MSG: {}, CTX: {}", level, message, context);
+///   fn log(&mut self, record: &Record) -> Result<(), Error> {
+///     println!(
+///       "{} LEVEL: {}, MSG: {}, CTX: {}",
+///       record.timestamp(),
+///       record.level(),
+///       record.message(),
+///       record.context()
+///     );
+///     Ok(())
 ///   }
 /// }
 /// ```
@@ -24,13 +34,101 @@ pub trait Target {
   /// You shouldn't need to call `log` directly. `log` is called by logger
   /// internally.
   ///
-  /// Log a message to each target contained within the `TargetSet`.
+  /// Log a message. Errors are passed to the logger's
+  /// [`ErrorHandler`](../trait.ErrorHandler.html).
+  ///
+  /// # Arguments
+  ///
+  /// * `record` - The record of the message to log.
+  fn log(&mut self, record: &Record) -> Result<(), Error>;
+
+  /// You shouldn't need to call `flush` directly. `flush` is called by
+  /// logger internally.
+  ///
+  /// Flush any messages the target has buffered. Targets that do not buffer
+  /// can rely on the default implementation, which does nothing.
+  fn flush(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  /// You shouldn't need to call `reopen` directly. `reopen` is called by
+  /// [`Logger::reopen`](../struct.Logger.html#method.reopen).
+  ///
+  /// Close and reopen any files the target writes to, so that a file
+  /// renamed by an external tool such as logrotate is replaced by a new one
+  /// at the original path. Targets that do not write to files can rely on
+  /// the default implementation, which does nothing.
+  fn reopen(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+/// The three argument form of [`Target`](trait.Target.html) used before
+/// targets were given a [`Record`](../struct.Record.html).
+///
+/// Every `BasicTarget` is a `Target`, so an existing target only needs its
+/// `impl Target` changed to `impl BasicTarget` to keep working. It receives
+/// the level, message and context of each record and nothing else.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// # use quil::Level;
+/// # use quil::Error;
+/// # use quil::targets::BasicTarget;
+/// struct Print;
+///
+/// impl BasicTarget for Print {
+///   fn log(&mut self, level: Level, message: &str, context: &Context) -> Result<(), Error> {
+///     println!("LEVEL: {}, MSG: {}, CTX: {}", level, message, context);
+///     Ok(())
+///   }
+/// }
+///
+/// let logger = Logger::new(Print, context!{ src: "root" });
+/// logger.info("hello");
+/// # }
+/// ```
+pub trait BasicTarget {
+  /// Log a message. See [`Target::log`](trait.Target.html#tymethod.log).
   ///
   /// # Arguments
   ///
   /// * `level` - The log level the message is associated with.
   /// * `message` - The message to log.
   /// * `context` - The context containing meta data associated with the
-  ///               message.
-  fn log(&mut self, level: Level, message: &str, context: &Context);
+  ///   message.
+  fn log(&mut self, level: Level, message: &str, context: &Context) -> Result<(), Error>;
+
+  /// Flush any messages the target has buffered. See
+  /// [`Target::flush`](trait.Target.html#method.flush).
+  fn flush(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  /// Reopen any files the target writes to. See
+  /// [`Target::reopen`](trait.Target.html#method.reopen).
+  fn reopen(&mut self) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+impl<T> Target for T
+where
+  T: BasicTarget,
+{
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    BasicTarget::log(self, record.level(), record.message(), record.context())
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    BasicTarget::flush(self)
+  }
+
+  fn reopen(&mut self) -> Result<(), Error> {
+    BasicTarget::reopen(self)
+  }
 }
@@ -1,5 +1,5 @@
-use super::super::Context;
-use super::super::Level;
+use super::super::Error;
+use super::super::Record;
 use super::Target;
 
 /// Creates a [`TargetSet`](struct.TargetSet.html) from a varying set of
@@ -24,9 +24,11 @@ macro_rules! targets {
 /// [`Target`](trait.Target.html). `TargetSet` also implements
 /// [`Target`](trait.Target.html) so it can be passed to
 /// [`Logger`](struct.Logger.html). A message logged to the `TargetSet`
-/// will be logged to each of the targets within it.
+/// will be logged to each of the targets within it. If a target fails the
+/// message is still logged to the remaining targets, and the failures are
+/// returned together.
 pub struct TargetSet<'a> {
-  targets: Vec<Box<Target + 'a>>,
+  targets: Vec<Box<dyn Target + Send + 'a>>,
 }
 
 impl<'a> TargetSet<'a> {
@@ -45,16 +47,40 @@ impl<'a> TargetSet<'a> {
   ///
   pub fn add<T>(&mut self, target: T)
   where
-    T: Target + 'a,
+    T: Target + Send + 'a,
   {
     self.targets.push(Box::new(target));
   }
 }
 
+impl<'a> Default for TargetSet<'a> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl<'a> Target for TargetSet<'a> {
-  fn log(&mut self, level: Level, message: &str, context: &Context) {
-    for target in self.targets.iter_mut() {
-      target.log(level, message, context);
-    }
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    collect_errors(self.targets.iter_mut().map(|target| target.log(record)))
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    collect_errors(self.targets.iter_mut().map(|target| target.flush()))
+  }
+
+  fn reopen(&mut self) -> Result<(), Error> {
+    collect_errors(self.targets.iter_mut().map(|target| target.reopen()))
+  }
+}
+
+fn collect_errors<I>(results: I) -> Result<(), Error>
+where
+  I: Iterator<Item = Result<(), Error>>,
+{
+  let mut errors: Vec<_> = results.filter_map(Result::err).collect();
+  match errors.len() {
+    0 => Ok(()),
+    1 => Err(errors.remove(0)),
+    _ => Err(Error::Targets(errors)),
   }
 }
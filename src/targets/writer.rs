@@ -0,0 +1,198 @@
+use std::io::{self, BufWriter, Write};
+use std::mem;
+use super::super::Error;
+use super::super::Record;
+use super::super::formatters::style;
+use super::super::formatters::{Formatter, Text};
+use super::Target;
+
+/// Contains configuration options for a `Writer` object.
+///
+/// By default lines are formatted with a plain [`Text`](../formatters/struct.Text.html)
+/// formatter, collected in an 8 KiB buffer, and flushed after every line.
+pub struct WriterOptions {
+  formatter: Box<dyn Formatter + Send>,
+  buffer_capacity: usize,
+  line_flush: bool,
+  strip_color: bool,
+}
+
+impl WriterOptions {
+  /// Creates a new instance of `WriterOptions`.
+  pub fn new() -> Self {
+    Self {
+      formatter: Box::new(Text::new()),
+      buffer_capacity: 8 * 1024,
+      line_flush: true,
+      strip_color: false,
+    }
+  }
+
+  /// Set the formatter used to write each line.
+  pub fn formatter<F>(mut self, formatter: F) -> Self
+  where
+    F: Formatter + Send + 'static,
+  {
+    self.formatter = Box::new(formatter);
+    self
+  }
+
+  /// Set the size of the buffer lines are collected in before being written.
+  /// With a capacity of 0 every line is written straight through.
+  pub fn buffer_capacity(mut self, buffer_capacity: usize) -> Self {
+    self.buffer_capacity = buffer_capacity;
+    self
+  }
+
+  /// Enable/disable flushing after every line. When disabled, lines are
+  /// written once the buffer fills, the target is flushed, or the `Writer`
+  /// is dropped.
+  pub fn line_flush(mut self, line_flush: bool) -> Self {
+    self.line_flush = line_flush;
+    self
+  }
+
+  /// Enable/disable removing ANSI colour escape sequences written by the
+  /// formatter.
+  pub fn strip_color(mut self, strip_color: bool) -> Self {
+    self.strip_color = strip_color;
+    self
+  }
+
+  /// Create a `Writer` instance writing to the given writer from the options
+  /// set.
+  ///
+  /// # Arguments
+  ///
+  /// * `writer` - Where lines are written, such as a socket or a `Vec<u8>`.
+  pub fn build<W>(self, writer: W) -> Writer<W>
+  where
+    W: Write + Send,
+  {
+    Writer {
+      writer: BufWriter::with_capacity(self.buffer_capacity, writer),
+      formatter: self.formatter,
+      line_flush: self.line_flush,
+      strip_color: self.strip_color,
+    }
+  }
+}
+
+impl Default for WriterOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A target which formats each message as a line and writes it to any
+/// `io::Write`.
+///
+/// Each line is passed to the underlying writer whole, together with its
+/// newline, unless it is longer than the buffer. Lines from several
+/// processes appending to the same file or pipe are therefore not
+/// interleaved.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use std::net::TcpStream;
+/// use quil::formatters::Json;
+/// use quil::targets::{Writer, WriterOptions};
+///
+/// let to_stderr = Writer::new(std::io::stderr());
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+/// # let address = listener.local_addr().unwrap();
+/// let to_socket = WriterOptions::new()
+///   .formatter(Json::new())
+///   .line_flush(false)
+///   .build(TcpStream::connect(address).unwrap());
+///
+/// let logger = Logger::new(targets![to_stderr, to_socket], context!{ src: "db" });
+/// logger.warn("slow query");
+/// logger.flush().unwrap();
+/// # }
+/// ```
+pub struct Writer<W>
+where
+  W: Write + Send,
+{
+  writer: BufWriter<W>,
+  formatter: Box<dyn Formatter + Send>,
+  line_flush: bool,
+  strip_color: bool,
+}
+
+impl<W> Writer<W>
+where
+  W: Write + Send,
+{
+  /// Creates a new `Writer` with default options writing to the given
+  /// writer.
+  ///
+  /// # Arguments
+  ///
+  /// * `writer` - Where lines are written, such as a socket or a `Vec<u8>`.
+  pub fn new(writer: W) -> Self {
+    WriterOptions::new().build(writer)
+  }
+
+  /// Returns a reference to the underlying writer.
+  pub fn get_ref(&self) -> &W {
+    self.writer.get_ref()
+  }
+
+  /// Returns a mutable reference to the underlying writer. Writing to it
+  /// directly while lines are buffered will reorder the output.
+  pub fn get_mut(&mut self) -> &mut W {
+    self.writer.get_mut()
+  }
+
+  /// Flushes any buffered lines and returns the underlying writer.
+  pub fn into_inner(self) -> Result<W, io::Error> {
+    self.writer.into_inner().map_err(|error| error.into_error())
+  }
+
+  /// Flushes any buffered lines to the underlying writer, then replaces it
+  /// with the given writer and returns the old one. The old writer is kept
+  /// if flushing fails.
+  ///
+  /// # Arguments
+  ///
+  /// * `writer` - Where lines are written from now on.
+  pub fn replace(&mut self, writer: W) -> Result<W, io::Error> {
+    self.writer.flush()?;
+    let capacity = self.writer.capacity();
+    let old = mem::replace(&mut self.writer, BufWriter::with_capacity(capacity, writer));
+    old.into_inner().map_err(|error| error.into_error())
+  }
+
+  /// Writes a line which has already been formatted, adding the newline.
+  pub(crate) fn write_line(&mut self, line: &str) -> Result<(), Error> {
+    let mut line = if self.strip_color { style::strip(line) } else { line.to_string() };
+    line.push('\n');
+    self.writer.write_all(line.as_bytes())?;
+    if self.line_flush {
+      self.writer.flush()?;
+    }
+    Ok(())
+  }
+}
+
+impl<W> Target for Writer<W>
+where
+  W: Write + Send,
+{
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    let line = self.formatter.format(record);
+    self.write_line(&line)
+  }
+
+  fn flush(&mut self) -> Result<(), Error> {
+    self.writer.flush()?;
+    Ok(())
+  }
+}
@@ -0,0 +1,285 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{self, Event, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context as LayerContext, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use super::Context;
+use super::Level;
+use super::Logger;
+
+/// A `tracing_subscriber` layer that forwards `tracing` events and spans to
+/// a [`Logger`](../struct.Logger.html), and therefore to the logger's
+/// target.
+///
+/// Each event is logged with the fields of every span it was recorded within
+/// flattened into the context, outermost span first, followed by the
+/// event's own fields. The event's `message` field becomes the logged
+/// message. The event's `target`, `module_path`, `file` and `line` are added
+/// to the context as `target`, `module`, `file` and `line`, and the name of
+/// the innermost span as `span`.
+///
+/// Span enter and exit are logged at `Trace` with the span's fields. Exits
+/// also carry `duration`, the time in microseconds spent within the span
+/// since it was entered, and `busy`, the total time in microseconds spent
+/// within the span across every entry so far. Span events can be disabled
+/// with [`span_events`](#method.span_events).
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// #[macro_use] extern crate tracing;
+/// extern crate tracing_subscriber;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// use quil::tracing_layer::TracingLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let logger = Logger::new(Console::new(), context!{ src: "root" });
+/// let subscriber = tracing_subscriber::registry().with(TracingLayer::new(logger));
+///
+/// tracing::subscriber::with_default(subscriber, || {
+///   let span = info_span!("request", id = 7);
+///   let _entered = span.enter();
+///
+///   info!(user = "bob", "logged in");
+/// });
+/// # }
+/// ```
+pub struct TracingLayer {
+  logger: Logger,
+  span_events: bool,
+}
+
+impl TracingLayer {
+  /// Creates a new layer that logs to the given logger.
+  ///
+  /// # Arguments
+  ///
+  /// * `logger` - The logger `tracing` events should be sent to.
+  pub fn new(logger: Logger) -> Self {
+    Self {
+      logger,
+      span_events: true,
+    }
+  }
+
+  /// Enable/disable logging of span enter and exit. Enabled by default.
+  pub fn span_events(mut self, span_events: bool) -> Self {
+    self.span_events = span_events;
+    self
+  }
+
+  fn log(&self, level: Level, message: &str, context: Context) {
+    self.logger.ctx(context).log(level, message);
+  }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+  S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  fn on_new_span(&self, attrs: &Attributes, id: &Id, ctx: LayerContext<S>) {
+    let span = match ctx.span(id) {
+      Some(span) => span,
+      None => return,
+    };
+    let mut fields = SpanFields(Context::new());
+    attrs.record(&mut FieldVisitor {
+      context: &mut fields.0,
+      message: None,
+    });
+
+    let mut extensions = span.extensions_mut();
+    extensions.insert(fields);
+    extensions.insert(SpanTiming {
+      entered: None,
+      busy: Duration::from_secs(0),
+    });
+  }
+
+  fn on_record(&self, id: &Id, values: &Record, ctx: LayerContext<S>) {
+    let span = match ctx.span(id) {
+      Some(span) => span,
+      None => return,
+    };
+    let mut extensions = span.extensions_mut();
+    if let Some(fields) = extensions.get_mut::<SpanFields>() {
+      values.record(&mut FieldVisitor {
+        context: &mut fields.0,
+        message: None,
+      });
+    }
+  }
+
+  fn on_event(&self, event: &Event, ctx: LayerContext<S>) {
+    let metadata = event.metadata();
+    let level = from_tracing_level(*metadata.level());
+    if level > self.logger.max_level() {
+      return;
+    }
+
+    let mut context = Context::new();
+    if let Some(scope) = ctx.event_scope(event) {
+      for span in scope.from_root() {
+        if let Some(fields) = span.extensions().get::<SpanFields>() {
+          context = context.merge(fields.0.clone());
+        }
+        context.set("span", span.name());
+      }
+    }
+    context = context.merge(metadata_context(metadata));
+
+    let mut message = String::new();
+    event.record(&mut FieldVisitor {
+      context: &mut context,
+      message: Some(&mut message),
+    });
+
+    self.logger.ctx(context).log_at(
+      level,
+      &message,
+      metadata.file(),
+      metadata.line(),
+      metadata.module_path(),
+    );
+  }
+
+  fn on_enter(&self, id: &Id, ctx: LayerContext<S>) {
+    let span = match ctx.span(id) {
+      Some(span) => span,
+      None => return,
+    };
+    let mut context = {
+      let mut extensions = span.extensions_mut();
+      if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+        timing.entered = Some(Instant::now());
+      }
+      span_context(extensions.get_mut::<SpanFields>().map(|fields| &*fields))
+    };
+
+    if !self.span_events || Level::Trace > self.logger.max_level() {
+      return;
+    }
+    context.set("span", span.name());
+    self.log(Level::Trace, &format!("enter {}", span.name()), context);
+  }
+
+  fn on_exit(&self, id: &Id, ctx: LayerContext<S>) {
+    let span = match ctx.span(id) {
+      Some(span) => span,
+      None => return,
+    };
+    let (mut context, durations) = {
+      let mut extensions = span.extensions_mut();
+      let mut durations = None;
+      if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+        if let Some(entered) = timing.entered.take() {
+          let duration = entered.elapsed();
+          timing.busy += duration;
+          durations = Some((duration, timing.busy));
+        }
+      }
+      (span_context(extensions.get_mut::<SpanFields>().map(|fields| &*fields)), durations)
+    };
+
+    if !self.span_events || Level::Trace > self.logger.max_level() {
+      return;
+    }
+    context.set("span", span.name());
+    if let Some((duration, busy)) = durations {
+      context.set("duration", duration.as_micros() as u64);
+      context.set("busy", busy.as_micros() as u64);
+    }
+    self.log(Level::Trace, &format!("exit {}", span.name()), context);
+  }
+}
+
+fn from_tracing_level(level: tracing_core::Level) -> Level {
+  match level {
+    tracing_core::Level::ERROR => Level::Error,
+    tracing_core::Level::WARN => Level::Warn,
+    tracing_core::Level::INFO => Level::Info,
+    tracing_core::Level::DEBUG => Level::Debug,
+    tracing_core::Level::TRACE => Level::Trace,
+  }
+}
+
+fn metadata_context(metadata: &Metadata) -> Context {
+  let mut context = Context::new();
+  context.set("target", metadata.target());
+  if let Some(module_path) = metadata.module_path() {
+    context.set("module", module_path);
+  }
+  if let Some(file) = metadata.file() {
+    context.set("file", file);
+  }
+  if let Some(line) = metadata.line() {
+    context.set("line", line);
+  }
+  context
+}
+
+fn span_context(fields: Option<&SpanFields>) -> Context {
+  fields
+    .map(|fields| fields.0.clone())
+    .unwrap_or_default()
+}
+
+struct SpanFields(Context);
+
+struct SpanTiming {
+  entered: Option<Instant>,
+  busy: Duration,
+}
+
+// Records fields into a context. When given somewhere to put it, the
+// `message` field is taken as the message instead.
+struct FieldVisitor<'a> {
+  context: &'a mut Context,
+  message: Option<&'a mut String>,
+}
+
+impl<'a> FieldVisitor<'a> {
+  fn message(&mut self, field: &Field) -> Option<&mut String> {
+    if field.name() == "message" {
+      self.message.as_deref_mut()
+    } else {
+      None
+    }
+  }
+}
+
+impl<'a> Visit for FieldVisitor<'a> {
+  fn record_i64(&mut self, field: &Field, value: i64) {
+    self.context.set(field.name(), value);
+  }
+
+  fn record_u64(&mut self, field: &Field, value: u64) {
+    self.context.set(field.name(), value);
+  }
+
+  fn record_f64(&mut self, field: &Field, value: f64) {
+    self.context.set(field.name(), value);
+  }
+
+  fn record_bool(&mut self, field: &Field, value: bool) {
+    self.context.set(field.name(), value);
+  }
+
+  fn record_str(&mut self, field: &Field, value: &str) {
+    match self.message(field) {
+      Some(message) => *message = value.to_string(),
+      None => self.context.set(field.name(), value),
+    }
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+    match self.message(field) {
+      Some(message) => *message = format!("{:?}", value),
+      None => self.context.set(field.name(), format!("{:?}", value)),
+    }
+  }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// A value stored within a [`Context`](struct.Context.html).
+///
+/// Values keep their type so that targets can render them in their own
+/// native form. [`JsonFile`](targets/struct.JsonFile.html) for example
+/// writes numbers and booleans as real JSON numbers and booleans.
+///
+/// Most values are created through the `From` implementations, which is what
+/// allows [`context!`](macro.context.html) to accept strings, numbers,
+/// booleans, vectors, maps and options directly.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate quil;
+/// # fn main() {
+/// # use quil::prelude::*;
+/// let ctx = context!{ src: "root", retries: 3, ok: true };
+///
+/// assert_eq!(ctx.get("src").unwrap(), "root");
+/// assert_eq!(ctx.get("retries").unwrap(), &Value::I64(3));
+/// assert_eq!(ctx.get("ok").unwrap(), &Value::Bool(true));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+  String(String),
+  I64(i64),
+  U64(u64),
+  F64(f64),
+  Bool(bool),
+  Null,
+  Array(Vec<Value>),
+  Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+  /// Returns the contained string if the value is a string.
+  pub fn as_str(&self) -> Option<&str> {
+    match *self {
+      Value::String(ref value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Returns the value as an `i64` if it is an integer that fits.
+  pub fn as_i64(&self) -> Option<i64> {
+    match *self {
+      Value::I64(value) => Some(value),
+      Value::U64(value) if value <= i64::MAX as u64 => Some(value as i64),
+      _ => None,
+    }
+  }
+
+  /// Returns the value as a `u64` if it is a non-negative integer.
+  pub fn as_u64(&self) -> Option<u64> {
+    match *self {
+      Value::I64(value) if value >= 0 => Some(value as u64),
+      Value::U64(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Returns the value as an `f64` if it is a number.
+  pub fn as_f64(&self) -> Option<f64> {
+    match *self {
+      Value::I64(value) => Some(value as f64),
+      Value::U64(value) => Some(value as f64),
+      Value::F64(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Returns the contained boolean if the value is a boolean.
+  pub fn as_bool(&self) -> Option<bool> {
+    match *self {
+      Value::Bool(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Returns true if the value is `Null`.
+  pub fn is_null(&self) -> bool {
+    *self == Value::Null
+  }
+
+  /// Creates a string value from bytes which may not be valid UTF-8.
+  /// Invalid sequences are replaced with `U+FFFD`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use quil::prelude::*;
+  /// assert_eq!(Value::from_utf8_lossy(b"ok \xff"), "ok \u{fffd}");
+  /// ```
+  pub fn from_utf8_lossy(bytes: &[u8]) -> Self {
+    Value::String(String::from_utf8_lossy(bytes).into_owned())
+  }
+
+  /// Creates a string value from UTF-16 which may contain lone surrogates,
+  /// such as a Windows path or a string from JavaScript. Lone surrogates are
+  /// replaced with `U+FFFD`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use quil::prelude::*;
+  /// assert_eq!(Value::from_utf16_lossy(&[0x68, 0xd800, 0x69]), "h\u{fffd}i");
+  /// ```
+  pub fn from_utf16_lossy(units: &[u16]) -> Self {
+    Value::String(String::from_utf16_lossy(units))
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Value::String(ref value) => write!(f, "{}", value),
+      Value::I64(value) => write!(f, "{}", value),
+      Value::U64(value) => write!(f, "{}", value),
+      Value::F64(value) => write!(f, "{}", value),
+      Value::Bool(value) => write!(f, "{}", value),
+      Value::Null => write!(f, "null"),
+      Value::Array(ref values) => {
+        let values: Vec<_> = values.iter().map(|val| val.to_string()).collect();
+        write!(f, "[{}]", values.join(", "))
+      }
+      Value::Map(ref values) => {
+        let values: Vec<_> = values
+          .iter()
+          .map(|(key, val)| format!("{}: {}", key, val))
+          .collect();
+        write!(f, "{{{}}}", values.join(", "))
+      }
+    }
+  }
+}
+
+impl<'a> From<&'a str> for Value {
+  fn from(value: &'a str) -> Self {
+    Value::String(value.to_string())
+  }
+}
+
+impl<'a> From<&'a String> for Value {
+  fn from(value: &'a String) -> Self {
+    Value::String(value.clone())
+  }
+}
+
+impl From<String> for Value {
+  fn from(value: String) -> Self {
+    Value::String(value)
+  }
+}
+
+impl<'a> From<&'a OsStr> for Value {
+  fn from(value: &'a OsStr) -> Self {
+    Value::String(value.to_string_lossy().into_owned())
+  }
+}
+
+impl<'a> From<&'a Path> for Value {
+  fn from(value: &'a Path) -> Self {
+    Value::String(value.to_string_lossy().into_owned())
+  }
+}
+
+macro_rules! impl_from_number {
+  ($variant:ident, $into:ty, $($from:ty),*) => {
+    $(
+      impl From<$from> for Value {
+        fn from(value: $from) -> Self {
+          Value::$variant(value as $into)
+        }
+      }
+    )*
+  }
+}
+
+impl_from_number!(I64, i64, i8, i16, i32, i64, isize);
+impl_from_number!(U64, u64, u8, u16, u32, u64, usize);
+impl_from_number!(F64, f64, f32, f64);
+
+impl From<bool> for Value {
+  fn from(value: bool) -> Self {
+    Value::Bool(value)
+  }
+}
+
+impl<T> From<Option<T>> for Value
+where
+  T: Into<Value>,
+{
+  fn from(value: Option<T>) -> Self {
+    match value {
+      Some(value) => value.into(),
+      None => Value::Null,
+    }
+  }
+}
+
+impl<T> From<Vec<T>> for Value
+where
+  T: Into<Value>,
+{
+  fn from(values: Vec<T>) -> Self {
+    Value::Array(values.into_iter().map(Into::into).collect())
+  }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for Value
+where
+  K: Into<String>,
+  V: Into<Value>,
+{
+  fn from(values: BTreeMap<K, V>) -> Self {
+    Value::Map(
+      values
+        .into_iter()
+        .map(|(key, val)| (key.into(), val.into()))
+        .collect(),
+    )
+  }
+}
+
+impl<K, V> From<HashMap<K, V>> for Value
+where
+  K: Into<String>,
+  V: Into<Value>,
+{
+  fn from(values: HashMap<K, V>) -> Self {
+    Value::Map(
+      values
+        .into_iter()
+        .map(|(key, val)| (key.into(), val.into()))
+        .collect(),
+    )
+  }
+}
+
+impl PartialEq<str> for Value {
+  fn eq(&self, other: &str) -> bool {
+    self.as_str() == Some(other)
+  }
+}
+
+impl<'a> PartialEq<&'a str> for Value {
+  fn eq(&self, other: &&'a str) -> bool {
+    self.as_str() == Some(*other)
+  }
+}
+
+impl PartialEq<String> for Value {
+  fn eq(&self, other: &String) -> bool {
+    self.as_str() == Some(other.as_str())
+  }
+}
@@ -0,0 +1,250 @@
+//! Checks how `AsyncTarget` handles a full queue, flushes, drops and a
+//! worker which stops after a panic. The wrapped target is gated so the
+//! tests decide when the worker gets past each message.
+
+#[macro_use]
+extern crate quil;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+use quil::error_handlers::Count;
+use quil::prelude::*;
+use quil::targets::{AsyncTarget, AsyncTargetOptions, Dump, Overflow, Target};
+use quil::{Error as QuilError, Record};
+
+// Tells the test when the worker has taken a message, then waits for a
+// token before logging it. Dropping the sender opens the gate for good.
+struct Gated {
+  dump: Dump,
+  entered: Sender<()>,
+  release: Receiver<()>,
+}
+
+impl Target for Gated {
+  fn log(&mut self, record: &Record) -> Result<(), QuilError> {
+    let _ = self.entered.send(());
+    let _ = self.release.recv();
+    self.dump.log(record)
+  }
+}
+
+struct Gate {
+  entered: Receiver<()>,
+  release: Sender<()>,
+}
+
+fn gated(options: AsyncTargetOptions, dump: &Dump) -> (AsyncTarget, Gate) {
+  let (entered, entered_receiver) = mpsc::channel();
+  let (release_sender, release) = mpsc::channel();
+  let target = options.build(Gated {
+    dump: dump.clone(),
+    entered,
+    release,
+  });
+  let gate = Gate {
+    entered: entered_receiver,
+    release: release_sender,
+  };
+  (target, gate)
+}
+
+fn messages(dump: &Dump) -> Vec<String> {
+  dump.dump_messages().into_iter().map(|message| message.1).collect()
+}
+
+// Logs "a" and waits for the worker to hold it at the gate, then fills the
+// queue with the other messages.
+fn fill(logger: &Logger, gate: &Gate, queued: &[&str]) {
+  logger.info("a");
+  gate.entered.recv().unwrap();
+  for message in queued {
+    logger.info(message);
+  }
+}
+
+#[test]
+fn block_waits_for_room() {
+  let dump = Dump::new();
+  let (target, gate) = gated(AsyncTargetOptions::new().capacity(1), &dump);
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+  fill(&logger, &gate, &["b"]);
+
+  let logged = Arc::new(AtomicUsize::new(0));
+  let blocked = {
+    let (logger, logged) = (logger.clone(), logged.clone());
+    thread::spawn(move || {
+      logger.info("c");
+      logged.store(1, Ordering::SeqCst);
+    })
+  };
+  thread::sleep(Duration::from_millis(50));
+  assert_eq!(logged.load(Ordering::SeqCst), 0);
+
+  drop(gate.release);
+  blocked.join().unwrap();
+  handle.flush().unwrap();
+  assert_eq!(messages(&dump), ["a", "b", "c"]);
+  assert_eq!(handle.dropped(), 0);
+}
+
+#[test]
+fn drop_newest_discards_the_message_logged() {
+  let dump = Dump::new();
+  let options = AsyncTargetOptions::new().capacity(2).overflow(Overflow::DropNewest);
+  let (target, gate) = gated(options, &dump);
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+  fill(&logger, &gate, &["b", "c", "d", "e"]);
+  assert_eq!(handle.dropped(), 2);
+
+  drop(gate.release);
+  handle.flush().unwrap();
+  assert_eq!(messages(&dump), ["a", "b", "c"]);
+}
+
+#[test]
+fn drop_oldest_discards_the_oldest_queued_message() {
+  let dump = Dump::new();
+  let options = AsyncTargetOptions::new().capacity(2).overflow(Overflow::DropOldest);
+  let (target, gate) = gated(options, &dump);
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+  fill(&logger, &gate, &["b", "c", "d", "e"]);
+  assert_eq!(handle.dropped(), 2);
+
+  drop(gate.release);
+  handle.flush().unwrap();
+  assert_eq!(messages(&dump), ["a", "d", "e"]);
+}
+
+#[test]
+fn dropping_drains_the_queue() {
+  let dump = Dump::new();
+  let (target, gate) = gated(AsyncTargetOptions::new(), &dump);
+  let logger = Logger::new(target, context!{});
+  fill(&logger, &gate, &["b", "c"]);
+
+  drop(gate.release);
+  drop(logger);
+  assert_eq!(messages(&dump), ["a", "b", "c"]);
+}
+
+#[test]
+fn flush_waits_for_messages_logged_before_it() {
+  let dump = Dump::new();
+  let (target, gate) = gated(AsyncTargetOptions::new(), &dump);
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+  fill(&logger, &gate, &["b"]);
+
+  let flushed = thread::spawn(move || handle.flush());
+  thread::sleep(Duration::from_millis(50));
+  assert!(dump.dump_messages().is_empty());
+
+  gate.release.send(()).unwrap();
+  gate.release.send(()).unwrap();
+  flushed.join().unwrap().unwrap();
+  assert_eq!(messages(&dump), ["a", "b"]);
+  drop(logger);
+}
+
+// Fails the first flush only.
+struct FailsOnce {
+  flushes: usize,
+  barrier: Arc<Barrier>,
+}
+
+impl Target for FailsOnce {
+  fn log(&mut self, _: &Record) -> Result<(), QuilError> {
+    self.barrier.wait();
+    Ok(())
+  }
+
+  fn flush(&mut self) -> Result<(), QuilError> {
+    self.flushes += 1;
+    if self.flushes == 1 {
+      return Err(QuilError::Other("disk full".to_string()));
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn each_flush_gets_its_own_result() {
+  let barrier = Arc::new(Barrier::new(2));
+  let target = AsyncTarget::new(FailsOnce {
+    flushes: 0,
+    barrier: barrier.clone(),
+  });
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+
+  // Holds the worker so that both flushes are queued before either runs.
+  logger.info("a");
+  let first = {
+    let handle = handle.clone();
+    thread::spawn(move || handle.flush())
+  };
+  thread::sleep(Duration::from_millis(20));
+  let second = {
+    let handle = handle.clone();
+    thread::spawn(move || handle.flush())
+  };
+  thread::sleep(Duration::from_millis(20));
+  barrier.wait();
+
+  assert!(first.join().unwrap().is_err());
+  assert!(second.join().unwrap().is_ok());
+}
+
+struct FailsToFlush;
+
+impl Target for FailsToFlush {
+  fn log(&mut self, _: &Record) -> Result<(), QuilError> {
+    Ok(())
+  }
+
+  fn flush(&mut self) -> Result<(), QuilError> {
+    Err(QuilError::Other("disk full".to_string()))
+  }
+}
+
+#[test]
+fn unwaited_flush_errors_go_to_the_error_handler() {
+  let count = Count::new();
+  let target = AsyncTargetOptions::new()
+    .error_handler(count.clone())
+    .build(FailsToFlush);
+  let guard = target.handle().guard();
+
+  drop(guard);
+  assert_eq!(count.count(), 1);
+  drop(target);
+  assert_eq!(count.count(), 2);
+}
+
+struct Panics;
+
+impl Target for Panics {
+  fn log(&mut self, _: &Record) -> Result<(), QuilError> {
+    panic!("target failed");
+  }
+}
+
+#[test]
+fn a_stopped_worker_does_not_block_loggers() {
+  let target = AsyncTargetOptions::new().capacity(1).build(Panics);
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+
+  logger.info("a");
+  assert!(handle.flush().is_err());
+  logger.info("b");
+  logger.info("c");
+  assert_eq!(handle.dropped(), 2);
+  assert!(logger.flush().is_err());
+}
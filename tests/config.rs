@@ -0,0 +1,162 @@
+//! Checks that config documents build the logger they describe, and that
+//! invalid documents are reported with the line of the error.
+
+#![cfg(feature = "config")]
+
+#[macro_use]
+extern crate quil;
+extern crate serde_json;
+
+use std::env;
+use std::fs;
+use quil::config::Config;
+use quil::prelude::*;
+use serde_json::Value as JsonValue;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+  let path = env::temp_dir().join(format!("quil-config-{}-{}", std::process::id(), name));
+  let _ = fs::remove_file(&path);
+  path
+}
+
+#[test]
+fn builds_a_logger_from_toml() {
+  let path = temp_path("toml.json");
+  let document = format!(
+    r#"
+level = "info"
+
+[levels]
+db = "trace"
+
+[context]
+service = "billing"
+port = 8080
+tags = ["a", "b"]
+
+[[targets]]
+type = "json_file"
+path = "{}"
+show_timestamp = false
+"#,
+    path.display()
+  );
+
+  let config = Config::from_toml(&document).unwrap();
+  assert_eq!(config.level(), Some(Info));
+  assert_eq!(config.context().get("port").unwrap(), &Value::I64(8080));
+
+  let logger = config.build().unwrap();
+  logger.debug("hidden");
+  logger.ctx(context!{ src: "db" }).trace("query");
+  logger.flush().unwrap();
+
+  let text = fs::read_to_string(&path).unwrap();
+  let lines: Vec<JsonValue> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+  assert_eq!(lines.len(), 1);
+  assert_eq!(lines[0]["message"], "query");
+  assert_eq!(lines[0]["context"]["service"], "billing");
+  assert_eq!(lines[0]["context"]["src"], "db");
+  let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn parses_json_and_files_by_extension() {
+  let path = temp_path("config.json");
+  fs::write(
+    &path,
+    r#"{
+  "level": 4,
+  "levels": { "http::client": "warning" },
+  "targets": [{ "type": "console", "stream": "stderr", "theme": "light" }]
+}"#,
+  )
+  .unwrap();
+
+  let config = Config::from_file(&path).unwrap();
+  assert_eq!(config.level(), Some(Debug));
+  assert_eq!(
+    config.filter().unwrap(),
+    quil::Filter::new().directive("http::client", Warn)
+  );
+  config.build().unwrap();
+  let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn reports_the_line_of_toml_errors() {
+  let error = Config::from_toml("level = \"info\"\n\n[[targets]]\ntype = \"tape\"\n").unwrap_err();
+  assert_eq!(error.line(), Some(4));
+  assert!(error.to_string().starts_with("invalid config on line 4: "));
+  assert!(error.to_string().contains("tape"));
+
+  let error = Config::from_toml("level = \"loud\"\n").unwrap_err();
+  assert_eq!(error.line(), Some(1));
+  assert!(error.to_string().contains("invalid level \"loud\""));
+
+  let error = Config::from_toml("\n[[targets]]\ntype = \"console\"\ncolour = \"never\"\n").unwrap_err();
+  // Errors inside a target are reported on the line the target starts on.
+  assert_eq!(error.line(), Some(2));
+  assert!(error.to_string().contains("unknown field `colour`"));
+
+  let error = Config::from_toml("level = 2\n[[targets]]\ntype = \"console\"\ntemplate = \"{nope}\"\n").unwrap_err();
+  assert_eq!(error.line(), Some(2));
+  assert!(error.to_string().contains("invalid template"));
+}
+
+#[test]
+fn reports_the_line_of_json_errors() {
+  let error = Config::from_json("{\n  \"level\": \"info\",\n  \"levels\": { \"db\": \"loud\" }\n}").unwrap_err();
+  assert_eq!(error.line(), Some(3));
+  assert!(error.to_string().starts_with("invalid config on line 3: invalid level \"loud\""));
+}
+
+#[test]
+fn applies_environment_overrides() {
+  env::set_var("QUIL_TEST_ENV_LEVEL", "trace");
+  env::set_var("QUIL_TEST_ENV_LEVELS", "db=error, http=warn");
+  env::set_var("QUIL_TEST_ENV_CONTEXT_REGION", "eu");
+
+  let config = Config::from_toml("level = \"info\"\n[levels]\ndb = \"debug\"\n")
+    .unwrap()
+    .env("QUIL_TEST_ENV")
+    .unwrap();
+  assert_eq!(config.level(), Some(Trace));
+  assert_eq!(
+    config.filter().unwrap(),
+    quil::Filter::new().directive("db", Error).directive("http", Warn)
+  );
+  assert_eq!(config.context().get("region").unwrap(), "eu");
+
+  env::set_var("QUIL_TEST_BAD_LEVELS", "db");
+  let error = Config::from_toml("").unwrap().env("QUIL_TEST_BAD").unwrap_err();
+  assert_eq!(error.variable(), Some("QUIL_TEST_BAD_LEVELS"));
+  assert_eq!(error.line(), None);
+}
+
+#[test]
+fn reports_targets_which_cannot_be_opened() {
+  let error = Config::from_toml(
+    "level = \"info\"\n\n[[targets]]\ntype = \"console\"\n\n[[targets]]\ntype = \"json_file\"\npath = \"/nonexistent/dir/log.json\"\n",
+  )
+  .unwrap()
+  .build()
+  .err()
+  .unwrap();
+  assert_eq!(error.line(), Some(6));
+  assert!(error
+    .to_string()
+    .starts_with("invalid config on line 6: failed to open target 2 (json_file): "));
+
+  let error = Config::from_json(
+    "{\n  \"targets\": [\n    { \"type\": \"console\" },\n    {\n      \"type\": \"json_file\",\n      \"path\": \"/nonexistent/dir/log.json\"\n    }\n  ]\n}",
+  )
+  .unwrap()
+  .build()
+  .err()
+  .unwrap();
+  assert_eq!(error.line(), Some(4));
+  assert!(error
+    .to_string()
+    .starts_with("invalid config on line 4: failed to open target 2 (json_file): "));
+}
@@ -0,0 +1,121 @@
+//! Checks that a watched config file is reapplied to a running logger when
+//! it changes or the process receives SIGHUP, and that an invalid change
+//! keeps the previous config.
+
+#![cfg(feature = "config")]
+
+extern crate quil;
+extern crate serde_json;
+#[cfg(all(unix, feature = "signals"))]
+extern crate signal_hook;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use quil::config::{self, WatchOptions};
+use quil::prelude::*;
+use serde_json::Value as JsonValue;
+
+fn temp_path(name: &str) -> PathBuf {
+  let path = env::temp_dir().join(format!("quil-watch-{}-{}", std::process::id(), name));
+  let _ = fs::remove_file(&path);
+  path
+}
+
+fn document(level: &str, log: &Path) -> String {
+  format!(
+    "level = \"{}\"\n\n[[targets]]\ntype = \"json_file\"\npath = \"{}\"\nshow_timestamp = false\n",
+    level,
+    log.display()
+  )
+}
+
+fn read_lines(path: &Path) -> Vec<JsonValue> {
+  fs::read_to_string(path)
+    .unwrap_or_default()
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect()
+}
+
+fn wait_for<F>(mut done: F)
+where
+  F: FnMut() -> bool,
+{
+  let start = Instant::now();
+  while !done() {
+    assert!(start.elapsed() < Duration::from_secs(5), "timed out waiting for a reload");
+    thread::sleep(Duration::from_millis(5));
+  }
+}
+
+#[test]
+fn reloads_on_change_and_keeps_the_old_config_on_failure() {
+  let path = temp_path("change.toml");
+  let first = temp_path("change-first.json");
+  let second = temp_path("change-second.json");
+
+  fs::write(&path, document("info", &first)).unwrap();
+  let logger = config::load(&path).unwrap();
+  let watcher = WatchOptions::new()
+    .interval(Duration::from_millis(10))
+    .signal(false)
+    .watch(&path, logger.clone())
+    .unwrap();
+  assert_eq!(watcher.path(), path.as_path());
+
+  logger.debug("hidden");
+  logger.info("first");
+
+  fs::write(&path, document("debug", &second)).unwrap();
+  wait_for(|| logger.level() == Debug);
+  logger.debug("second");
+  logger.flush().unwrap();
+
+  assert_eq!(read_lines(&first).len(), 1);
+  assert_eq!(read_lines(&first)[0]["message"], "first");
+  assert_eq!(read_lines(&second)[0]["message"], "second");
+
+  fs::write(&path, "level = \"debug\"\n\n[[targets]]\ntype = \"tape\"\n").unwrap();
+  wait_for(|| read_lines(&second).len() == 2);
+  let lines = read_lines(&second);
+  assert_eq!(lines[1]["level"], "error");
+  assert_eq!(lines[1]["context"]["config"], path.display().to_string());
+  assert!(lines[1]["context"]["error"]
+    .as_str()
+    .unwrap()
+    .starts_with("invalid config on line 4: "));
+  assert_eq!(logger.level(), Debug);
+
+  assert!(watcher.reload().is_err());
+  drop(watcher);
+
+  for path in &[path, first, second] {
+    let _ = fs::remove_file(path);
+  }
+}
+
+#[cfg(all(unix, feature = "signals"))]
+#[test]
+fn reloads_on_sighup() {
+  let path = temp_path("signal.toml");
+  let log = temp_path("signal.json");
+
+  fs::write(&path, document("info", &log)).unwrap();
+  let logger = config::load(&path).unwrap();
+  let watcher = WatchOptions::new()
+    .interval(Duration::from_millis(10))
+    .watch(&path, logger.clone())
+    .unwrap();
+
+  // The file is left unchanged, so only the signal triggers the reload.
+  logger.set_level(Error);
+  signal_hook::low_level::raise(signal_hook::consts::SIGHUP).unwrap();
+  wait_for(|| logger.level() == Info);
+
+  drop(watcher);
+  let _ = fs::remove_file(&path);
+  let _ = fs::remove_file(&log);
+}
@@ -0,0 +1,58 @@
+//! Checks which stream `Console` writes each level to and when it writes
+//! colour.
+
+extern crate quil;
+
+use std::ffi::OsStr;
+use quil::prelude::*;
+use quil::targets::{ColorMode, Stream};
+
+fn auto(clicolor_force: Option<&str>, no_color: Option<&str>, is_terminal: bool) -> bool {
+  ColorMode::Auto.enabled(
+    clicolor_force.map(OsStr::new),
+    no_color.map(OsStr::new),
+    is_terminal,
+  )
+}
+
+#[test]
+fn auto_follows_the_terminal() {
+  assert!(auto(None, None, true));
+  assert!(!auto(None, None, false));
+}
+
+#[test]
+fn no_color_disables_colour_unless_empty() {
+  assert!(!auto(None, Some("1"), true));
+  assert!(auto(None, Some(""), true));
+}
+
+#[test]
+fn clicolor_force_enables_colour_unless_zero() {
+  assert!(auto(Some("1"), None, false));
+  assert!(!auto(Some("0"), None, false));
+  assert!(auto(Some("0"), None, true));
+}
+
+#[test]
+fn clicolor_force_beats_no_color() {
+  assert!(auto(Some("1"), Some("1"), false));
+}
+
+#[test]
+fn always_and_never_ignore_the_environment() {
+  for &is_terminal in &[true, false] {
+    assert!(ColorMode::Always.enabled(None, Some(OsStr::new("1")), is_terminal));
+    assert!(!ColorMode::Never.enabled(Some(OsStr::new("1")), None, is_terminal));
+  }
+}
+
+#[test]
+fn streams_per_level() {
+  let levels = [Error, Warn, Info, Verbose, Debug, Trace];
+  for &level in &levels {
+    assert!(!Stream::Stdout.uses_stderr(level));
+    assert!(Stream::Stderr.uses_stderr(level));
+    assert_eq!(Stream::Split.uses_stderr(level), level <= Warn);
+  }
+}
@@ -0,0 +1,109 @@
+//! Checks that a failing target in a `TargetSet` does not stop the healthy
+//! ones, and that each error handler is given the set's errors.
+
+#[macro_use]
+extern crate quil;
+
+use std::sync::{Arc, Mutex};
+use quil::error_handlers::{Count, Fallback, Ignore};
+use quil::prelude::*;
+use quil::targets::{Dump, Target};
+use quil::{Error, Record};
+
+struct Broken;
+
+impl Target for Broken {
+  fn log(&mut self, _: &Record) -> Result<(), Error> {
+    Err(Error::Other("connection refused".to_string()))
+  }
+}
+
+const MESSAGES: [&str; 3] = ["a", "b", "c"];
+
+fn log_all(logger: &Logger) {
+  for message in &MESSAGES {
+    logger.info(message);
+  }
+}
+
+fn messages(dump: &Dump) -> Vec<String> {
+  dump.dump_messages().into_iter().map(|message| message.1).collect()
+}
+
+#[test]
+fn healthy_targets_keep_logging() {
+  let dump = Dump::new();
+  let logger = Logger::new(targets![Broken, dump.clone(), Broken], context!{});
+  logger.set_error_handler(Ignore);
+  log_all(&logger);
+  assert_eq!(messages(&dump), MESSAGES);
+}
+
+#[test]
+fn closures_receive_every_error() {
+  let dump = Dump::new();
+  let logger = Logger::new(targets![Broken, dump.clone(), Broken], context!{});
+  let seen = Arc::new(Mutex::new(Vec::new()));
+  {
+    let seen = seen.clone();
+    logger.set_error_handler(move |error: Error, record: &Record| {
+      seen.lock().unwrap().push((error, record.message().to_string()));
+    });
+  }
+  log_all(&logger);
+
+  assert_eq!(messages(&dump), MESSAGES);
+  let seen = seen.lock().unwrap();
+  assert_eq!(seen.len(), MESSAGES.len());
+  for ((error, message), expected) in seen.iter().zip(&MESSAGES) {
+    assert_eq!(message, expected);
+    match *error {
+      Error::Targets(ref errors) => {
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|error| error.to_string() == "connection refused"));
+      }
+      ref error => panic!("expected both targets' errors, got {}", error),
+    }
+  }
+}
+
+#[test]
+fn a_single_failure_is_not_wrapped() {
+  let dump = Dump::new();
+  let logger = Logger::new(targets![dump.clone(), Broken], context!{});
+  let seen = Arc::new(Mutex::new(Vec::new()));
+  {
+    let seen = seen.clone();
+    logger.set_error_handler(move |error: Error, _: &Record| {
+      seen.lock().unwrap().push(error.to_string());
+    });
+  }
+  log_all(&logger);
+
+  assert_eq!(messages(&dump), MESSAGES);
+  assert_eq!(*seen.lock().unwrap(), ["connection refused"; 3]);
+}
+
+#[test]
+fn count_counts_each_failed_message() {
+  let dump = Dump::new();
+  let logger = Logger::new(targets![Broken, dump.clone(), Broken], context!{});
+  let count = Count::new();
+  logger.set_error_handler(count.clone());
+  log_all(&logger);
+
+  assert_eq!(messages(&dump), MESSAGES);
+  assert_eq!(count.count(), MESSAGES.len());
+}
+
+#[test]
+fn fallback_logs_each_failed_message() {
+  let dump = Dump::new();
+  let fallback = Dump::new();
+  let logger = Logger::new(targets![Broken, dump.clone()], context!{});
+  logger.set_error_handler(Fallback::new(fallback.clone()));
+  log_all(&logger);
+
+  assert_eq!(messages(&dump), MESSAGES);
+  assert_eq!(messages(&fallback), MESSAGES);
+}
@@ -0,0 +1,165 @@
+//! Checks that errors logged with `error_with` keep their type name and
+//! source chain, and that `JsonFile` writes the chain as an array.
+
+#[macro_use]
+extern crate quil;
+extern crate serde_json;
+
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::process::Command;
+use quil::prelude::*;
+use quil::targets::{ColorMode, ConsoleOptions, Dump, JsonFileOptions, Stream};
+use serde_json::Value as JsonValue;
+
+#[derive(Debug)]
+struct LoadError {
+  source: ParseError,
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "failed to load settings")
+  }
+}
+
+impl error::Error for LoadError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    Some(&self.source)
+  }
+}
+
+#[derive(Debug)]
+struct ParseError {
+  source: io::Error,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "unexpected end of input")
+  }
+}
+
+impl error::Error for ParseError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    Some(&self.source)
+  }
+}
+
+fn load_error() -> LoadError {
+  LoadError {
+    source: ParseError {
+      source: io::Error::new(io::ErrorKind::UnexpectedEof, "file truncated"),
+    },
+  }
+}
+
+fn chain(values: &[&str]) -> Value {
+  Value::Array(values.iter().map(|value| Value::from(*value)).collect())
+}
+
+#[test]
+fn records_the_error_and_its_sources() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{ src: "settings" });
+
+  logger.error_with(&load_error(), "startup failed");
+  let boxed: Box<dyn error::Error> = Box::new(load_error());
+  logger.error_with(&*boxed, "startup failed");
+
+  let messages = dump.dump_messages();
+  assert_eq!(messages[0].0, Error);
+  assert_eq!(messages[0].1, "startup failed");
+  let context = &messages[0].2;
+  assert_eq!(context.get("src").unwrap(), "settings");
+  assert_eq!(context.get("error").unwrap(), "failed to load settings");
+  assert!(context.get("error_type").unwrap().as_str().unwrap().ends_with("LoadError"));
+  assert_eq!(
+    context.get("error_chain").unwrap(),
+    &chain(&["unexpected end of input", "file truncated"])
+  );
+
+  assert!(messages[1].2.get("error_type").is_none());
+  assert_eq!(messages[1].2.get("error_chain").unwrap(), context.get("error_chain").unwrap());
+}
+
+#[test]
+fn macros_add_the_error_to_the_context() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+
+  error_with!(logger, &load_error(), "attempt {} failed", 2; retrying = true);
+  error_with!(logger, &load_error(), "giving up");
+  logger.set_level(Error);
+  error_with!(logger, &load_error(), "{}", "still logged at error");
+
+  let messages = dump.dump_messages();
+  assert_eq!(messages.len(), 3);
+  assert_eq!(messages[0].1, "attempt 2 failed");
+  assert_eq!(messages[0].2.get("retrying").unwrap(), &Value::Bool(true));
+  assert_eq!(messages[0].2.get("error").unwrap(), "failed to load settings");
+  assert!(messages[1].2.get("error_type").unwrap().as_str().unwrap().ends_with("LoadError"));
+  assert_eq!(messages[2].1, "still logged at error");
+
+  let capture = quil::capture_global();
+  error_with!(&load_error(), "logged to the global logger");
+  let messages = capture.dump_messages();
+  assert_eq!(messages[0].1, "logged to the global logger");
+  assert_eq!(messages[0].2.get("error").unwrap(), "failed to load settings");
+}
+
+#[test]
+fn json_file_writes_the_chain_as_an_array() {
+  let path = env::temp_dir().join(format!("quil-error-with-{}.json", std::process::id()));
+  let _ = fs::remove_file(&path);
+
+  let target = JsonFileOptions::new(path.clone())
+    .show_timestamp(false)
+    .build()
+    .unwrap();
+  let logger = Logger::new(target, context!{});
+  logger.error_with(&load_error(), "startup failed");
+
+  let line: JsonValue = serde_json::from_str(fs::read_to_string(&path).unwrap().trim()).unwrap();
+  assert_eq!(line["context"]["error"], "failed to load settings");
+  assert_eq!(
+    line["context"]["error_chain"],
+    serde_json::json!(["unexpected end of input", "file truncated"])
+  );
+  let _ = fs::remove_file(&path);
+}
+
+// Console writes straight to stdout, so the test runs itself in a child
+// process to read what was printed.
+#[test]
+fn console_prints_details_only_for_error_with() {
+  if env::var_os("QUIL_ERROR_WITH_CHILD").is_some() {
+    let console = ConsoleOptions::new()
+      .show_timestamp(false)
+      .show_context(true)
+      .color(ColorMode::Never)
+      .stream(Stream::Stdout)
+      .build();
+    let logger = Logger::new(console, context!{});
+    logger.error_with(&load_error(), "startup failed");
+    let mut context = context!{ backtrace: "mine" };
+    context.set("error_chain", chain(&["also mine"]));
+    logger.ctx(context).error("user keys");
+    return;
+  }
+
+  let output = Command::new(env::current_exe().unwrap())
+    .args(["console_prints_details_only_for_error_with", "--exact", "--nocapture"])
+    .env("QUIL_ERROR_WITH_CHILD", "1")
+    .output()
+    .unwrap();
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("\n  caused by: unexpected end of input\n  caused by: file truncated\n"));
+  let line = stdout.lines().find(|line| line.contains("user keys")).unwrap();
+  assert!(line.contains("backtrace=mine"));
+  assert!(line.contains("also mine"));
+  assert!(!stdout.contains("caused by: also mine"));
+}
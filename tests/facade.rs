@@ -0,0 +1,34 @@
+//! Checks that only the installed logger, its clones and its sub-loggers
+//! change `log`'s max level. Everything runs in one test as `log` allows a
+//! single logger per process.
+
+#![cfg(feature = "log")]
+
+#[macro_use]
+extern crate quil;
+extern crate log;
+
+use quil::prelude::*;
+use quil::targets::Dump;
+
+#[test]
+fn tracks_the_installed_logger_tree() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  logger.set_level(Info);
+  quil::facade::install(logger.clone()).unwrap();
+  assert_eq!(log::max_level(), log::LevelFilter::Info);
+
+  logger.ctx(context!{ src: "db" }).set_level(Debug);
+  assert_eq!(log::max_level(), log::LevelFilter::Debug);
+
+  // Loggers built separately never change the installed tree's level.
+  let other = Logger::new(Dump::new(), context!{});
+  other.set_level(Error);
+  assert_eq!(log::max_level(), log::LevelFilter::Debug);
+
+  log::info!("from log");
+  let messages = dump.dump_messages();
+  assert_eq!(messages[0].1, "from log");
+  assert_eq!(messages[0].2.get("target").unwrap(), "facade");
+}
@@ -0,0 +1,69 @@
+//! Checks that filters match the module a message was logged from and the
+//! context given to the logging macros.
+
+#[macro_use]
+extern crate quil;
+
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::Filter;
+
+mod db {
+  pub mod pool {
+    use quil::prelude::*;
+
+    pub fn log(logger: &Logger) {
+      debug!(logger, "checked out a connection");
+    }
+  }
+}
+
+fn messages(dump: &Dump) -> Vec<String> {
+  dump.dump_messages().into_iter().map(|message| message.1).collect()
+}
+
+#[test]
+fn matches_the_module_path_without_a_context_value() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  logger.set_filter(Filter::parse("info,filter::db=debug").unwrap());
+
+  db::pool::log(&logger);
+  debug!(logger, "from the test module");
+  assert_eq!(messages(&dump), ["checked out a connection"]);
+
+  // A context value for the key takes precedence over the module path.
+  db::pool::log(&logger.ctx(context!{ src: "http" }));
+  assert!(dump.dump_messages().is_empty());
+}
+
+#[test]
+fn matches_the_context_given_to_the_macro() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{ src: "app" });
+  logger.set_filter(Filter::parse("error,db=trace").unwrap());
+
+  trace!(logger, "query {}", 1; src = "db");
+  debug!(logger, "hidden"; attempt = 2);
+  error_with!(logger, &std::fmt::Error, "failed"; src = "db");
+  assert_eq!(messages(&dump), ["query 1", "failed"]);
+
+  let db = logger.ctx(context!{ src: "db" });
+  trace!(db, "filtered"; src = "app");
+  assert!(dump.dump_messages().is_empty());
+}
+
+#[test]
+fn enabled_agrees_with_logging() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  logger.set_filter(Filter::parse("info,filter::db=debug").unwrap());
+
+  assert!(logger.enabled(Debug, Some("filter::db::pool")));
+  assert!(!logger.enabled(Debug, Some("filter")));
+  assert!(!logger.enabled(Debug, None));
+  assert!(!logger.ctx(context!{ src: "http" }).enabled(Debug, Some("filter::db::pool")));
+
+  db::pool::log(&logger);
+  assert_eq!(messages(&dump), ["checked out a connection"]);
+}
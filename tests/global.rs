@@ -0,0 +1,54 @@
+//! Checks the global logger's default, `set_global` and `capture_global`.
+//! Everything runs in one test as the global logger is shared by the whole
+//! test binary.
+
+#[macro_use]
+extern crate quil;
+
+use std::thread;
+use quil::prelude::*;
+use quil::targets::Dump;
+
+#[test]
+fn global_logger() {
+  // Logging before the global logger is set does nothing.
+  info!("dropped {}", 1);
+  quil::global().error("dropped");
+
+  {
+    let capture = quil::capture_global();
+    log!(Warn, "count is {}", 2; unit = "ms");
+    thread::spawn(|| error!("from another thread")).join().unwrap();
+
+    let messages = capture.dump_messages();
+    assert_eq!(messages.len(), 2);
+    assert_eq!((messages[0].0, &messages[0].1[..]), (Warn, "count is 2"));
+    assert_eq!(messages[0].2.get("unit").unwrap(), "ms");
+    assert_eq!((messages[1].0, &messages[1].1[..]), (Error, "from another thread"));
+  }
+
+  let dump = Dump::new();
+  quil::set_global(Logger::new(dump.clone(), context!{ src: "app" })).unwrap();
+  assert!(quil::set_global(Logger::new(Dump::new(), context!{})).is_err());
+
+  quil::global().set_level(Info);
+  debug!("filtered out");
+  info!("kept");
+  {
+    let capture = quil::capture_global();
+    info!("captured");
+    quil::global_ref().set_level(Error);
+    assert_eq!(capture.dump_messages().len(), 1);
+  }
+  info!("restored");
+  {
+    // Each capture starts from a logger at `Trace` without a filter.
+    let capture = quil::capture_global();
+    trace!("captured again");
+    assert_eq!(capture.dump_messages().len(), 1);
+  }
+  assert_eq!(quil::global_ref().level(), Info);
+
+  let messages: Vec<_> = dump.dump_messages().into_iter().map(|m| m.1).collect();
+  assert_eq!(messages, ["kept", "restored"]);
+}
@@ -0,0 +1,167 @@
+//! Checks that every line written by the `Json` formatter and `JsonFile`
+//! target parses as JSON and round-trips the original strings.
+
+extern crate proptest;
+#[macro_use]
+extern crate quil;
+extern crate serde_json;
+
+use std::char;
+use std::fs;
+use proptest::prelude::*;
+use quil::formatters::{Formatter, Json, Precision, Timezone};
+use quil::prelude::*;
+use quil::Record;
+use serde_json::Value as JsonValue;
+
+fn format(message: &str, context: &Context) -> JsonValue {
+  let line = Json::new().format(&Record::new(Info, message, context));
+  assert!(!line.contains('\n'), "line contains a raw newline: {:?}", line);
+  serde_json::from_str(&line).unwrap_or_else(|error| panic!("{}: {:?}", error, line))
+}
+
+fn assert_round_trip(message: &str, key: &str, value: &str) {
+  let mut context = Context::new();
+  context.set(key, value);
+
+  let parsed = format(message, &context);
+  assert_eq!(parsed["message"], message);
+  if !value.is_empty() {
+    assert_eq!(parsed["context"][key], value);
+  }
+}
+
+#[test]
+fn escapes_every_char() {
+  let chars: Vec<char> = (0..0x11_0000).filter_map(char::from_u32).collect();
+  for chunk in chars.chunks(512) {
+    let text: String = chunk.iter().collect();
+    assert_round_trip(&text, &text, &text);
+  }
+}
+
+#[test]
+fn escapes_control_chars_in_short_form() {
+  let context = Context::new();
+  let record = Record::new(Info, "\"\\\n\r\t\u{8}\u{c}\u{0}\u{1f}\u{7f}", &context);
+  let line = Json::new().format(&record);
+  assert!(line.contains(r#""\"\\\n\r\t\b\f\u0000\u001f\u007f""#), "{}", line);
+}
+
+#[test]
+fn escapes_nested_values() {
+  let context = context!{
+    list: vec!["a\nb", "c\"d"],
+    map: vec![("k\u{0}", "v\u{2028}")].into_iter().collect::<std::collections::BTreeMap<_, _>>(),
+    nan: f64::NAN
+  };
+
+  let parsed = format("nested", &context);
+  assert_eq!(parsed["context"]["list"][0], "a\nb");
+  assert_eq!(parsed["context"]["list"][1], "c\"d");
+  assert_eq!(parsed["context"]["map"]["k\u{0}"], "v\u{2028}");
+  assert!(parsed["context"]["nan"].is_null());
+}
+
+#[test]
+fn replaces_invalid_utf8_and_lone_surrogates() {
+  let mut context = Context::new();
+  context.set("bytes", Value::from_utf8_lossy(b"\xff\xfe\xc0\x80\xed\xa0\x80"));
+  context.set("utf16", Value::from_utf16_lossy(&[0xd800, 0x41, 0xdc00]));
+
+  let parsed = format("lossy", &context);
+  assert_eq!(parsed["context"]["bytes"], "\u{fffd}".repeat(7));
+  assert_eq!(parsed["context"]["utf16"], "\u{fffd}A\u{fffd}");
+}
+
+#[test]
+fn json_file_writes_valid_lines() {
+  let path = std::env::temp_dir().join("quil_json_round_trip.json");
+  let _ = fs::remove_file(&path);
+
+  let logger = Logger::new(JsonFile::open(path.clone()).unwrap(), context!{ src: "a\tb" });
+  logger.info("first\nline");
+  logger.warn("second \u{1b}[31mline");
+  logger.flush().unwrap();
+
+  let contents = fs::read_to_string(&path).unwrap();
+  let lines: Vec<JsonValue> = contents
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect();
+  assert_eq!(lines.len(), 2);
+  assert_eq!(lines[0]["message"], "first\nline");
+  assert_eq!(lines[0]["context"]["src"], "a\tb");
+  assert_eq!(lines[1]["message"], "second \u{1b}[31mline");
+}
+
+fn value_strategy() -> impl Strategy<Value = Value> {
+  let leaf = prop_oneof![
+    any::<String>().prop_map(Value::from),
+    any::<i64>().prop_map(Value::from),
+    any::<u64>().prop_map(Value::from),
+    any::<f64>().prop_map(Value::from),
+    any::<bool>().prop_map(Value::from),
+    Just(Value::Null),
+    any::<Vec<u8>>().prop_map(|bytes| Value::from_utf8_lossy(&bytes)),
+    any::<Vec<u16>>().prop_map(|units| Value::from_utf16_lossy(&units)),
+  ];
+  leaf.prop_recursive(3, 32, 8, |inner| {
+    prop_oneof![
+      prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+      prop::collection::btree_map(any::<String>(), inner, 0..8).prop_map(Value::Map),
+    ]
+  })
+}
+
+proptest! {
+  #[test]
+  fn strings_round_trip(message in any::<String>(), key in any::<String>(), value in any::<String>()) {
+    assert_round_trip(&message, &key, &value);
+  }
+
+  #[test]
+  fn lossy_bytes_round_trip(bytes in any::<Vec<u8>>()) {
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    assert_round_trip(&text, "bytes", Value::from_utf8_lossy(&bytes).as_str().unwrap());
+  }
+
+  #[test]
+  fn lossy_utf16_round_trip(units in any::<Vec<u16>>()) {
+    let text = String::from_utf16_lossy(&units);
+    assert_round_trip(&text, "utf16", Value::from_utf16_lossy(&units).as_str().unwrap());
+  }
+
+  #[test]
+  fn arbitrary_values_parse(
+    message in any::<String>(),
+    values in prop::collection::hash_map(any::<String>(), value_strategy(), 0..8),
+  ) {
+    let mut context = Context::new();
+    for (key, value) in values {
+      context.set(key, value);
+    }
+    let parsed = format(&message, &context);
+    prop_assert_eq!(parsed["message"].as_str(), Some(message.as_str()));
+    prop_assert_eq!(parsed["context"].as_object().map(|object| object.len()), Some(context.iter().count()));
+  }
+}
+
+#[test]
+fn optional_fields_parse() {
+  let json = Json::new()
+    .precision(Precision::Nanos)
+    .timezone(Timezone::Local)
+    .show_hostname(true)
+    .show_pid(true)
+    .show_thread(true);
+  let context = Context::new();
+  let line = json.format(&Record::new(Info, "fields", &context));
+  let parsed: JsonValue = serde_json::from_str(&line).unwrap();
+
+  let timestamp = parsed["timestamp"].as_str().unwrap();
+  assert_eq!(timestamp.len(), "2017-11-07T23:55:42.123456789+00:00".len());
+  assert_eq!(parsed["pid"], std::process::id());
+  assert_eq!(parsed["thread"], "optional_fields_parse");
+  assert!(parsed["hostname"].is_string());
+}
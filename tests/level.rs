@@ -0,0 +1,67 @@
+//! Checks level parsing and the fallible logger methods built on it.
+
+#[macro_use]
+extern crate quil;
+
+use std::convert::TryFrom;
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::{Filter, Level};
+
+const LEVELS: [Level; 6] = [Error, Warn, Info, Verbose, Debug, Trace];
+
+#[test]
+fn names_round_trip() {
+  for (n, &level) in LEVELS.iter().enumerate() {
+    assert_eq!(level.to_string().parse(), Ok(level));
+    assert_eq!(level.to_string().to_uppercase().parse(), Ok(level));
+    assert_eq!(n.to_string().parse(), Ok(level));
+    assert_eq!(Level::try_from(n as u8), Ok(level));
+  }
+}
+
+#[test]
+fn aliases() {
+  assert_eq!(Level::try_from(" Warning\n"), Ok(Warn));
+  assert_eq!(Level::try_from("ERR"), Ok(Error));
+  assert_eq!(Level::try_from("fatal"), Ok(Error));
+}
+
+#[test]
+fn invalid_levels() {
+  for input in &["", "loud", "6", "-1", "warnings", "in fo"] {
+    let error = input.parse::<Level>().unwrap_err();
+    assert_eq!(error.level(), *input);
+  }
+  assert_eq!(Level::try_from(6u8).unwrap_err().level(), "6");
+  assert_eq!(Level::try_from(255u8).unwrap_err().level(), "255");
+  assert_eq!(
+    "loud".parse::<Level>().unwrap_err().to_string(),
+    "invalid level \"loud\": expected error, warn, info, verbose, debug, trace or 0 to 5"
+  );
+}
+
+#[test]
+fn fallible_logger_methods() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+
+  assert!(logger.try_set_level("Info").is_ok());
+  assert!(logger.try_set_level("chatty").is_err());
+  assert_eq!(logger.level(), Info);
+
+  assert!(logger.try_log("warning", "kept").is_ok());
+  assert!(logger.try_log("4", "filtered").is_ok());
+  assert!(logger.try_log("shout", "invalid").is_err());
+
+  let messages: Vec<_> = dump.dump_messages().into_iter().map(|m| (m.0, m.1)).collect();
+  assert_eq!(messages, [(Warn, "kept".to_string())]);
+}
+
+#[test]
+fn filters_accept_aliases() {
+  let filter = Filter::parse("warning,db=5").unwrap();
+  assert_eq!(filter.default_level(), Some(Warn));
+  assert_eq!(filter.max_level(), Some(Trace));
+  assert!(Filter::parse("db=loud").is_err());
+}
@@ -0,0 +1,142 @@
+//! Checks that `Logfmt` lines quote, escape and order fields correctly by
+//! decoding them again.
+
+extern crate proptest;
+#[macro_use]
+extern crate quil;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use proptest::prelude::*;
+use quil::formatters::{self, Formatter};
+use quil::prelude::*;
+use quil::targets::Logfmt;
+use quil::Record;
+
+/// Decodes a logfmt line into its key and value pairs.
+fn decode(line: &str) -> Vec<(String, String)> {
+  let mut pairs = Vec::new();
+  let mut chars = line.chars().peekable();
+  while chars.peek().is_some() {
+    let key: String = chars.by_ref().take_while(|&c| c != '=').collect();
+    let mut value = String::new();
+    if chars.peek() == Some(&'"') {
+      chars.next();
+      while let Some(c) = chars.next() {
+        match c {
+          '"' => break,
+          '\\' => match chars.next().unwrap() {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            'u' => {
+              let hex: String = chars.by_ref().take(4).collect();
+              value.push(std::char::from_u32(u32::from_str_radix(&hex, 16).unwrap()).unwrap());
+            }
+            c => value.push(c),
+          },
+          c => value.push(c),
+        }
+      }
+      assert!(matches!(chars.next(), None | Some(' ')), "{:?}", line);
+    } else {
+      value = chars.by_ref().take_while(|&c| c != ' ').collect();
+    }
+    pairs.push((key, value));
+  }
+  pairs
+}
+
+fn format(message: &str, context: &Context) -> String {
+  formatters::Logfmt::new()
+    .show_timestamp(false)
+    .format(&Record::new(Info, message, context))
+}
+
+#[test]
+fn quotes_only_when_needed() {
+  let mut context = context!{
+    plain: "a\\b",
+    space: "a b",
+    equals: "a=b",
+    quote: "say \"hi\"",
+    newline: "a\nb",
+    number: 1.5,
+    list: vec![1, 2]
+  };
+  context.set("bad key=\"x\"", true);
+  context.set("none", Value::Null);
+
+  assert_eq!(
+    format("", &context),
+    "level=info msg=\"\" bad_key__x_=true equals=\"a=b\" list=\"[1, 2]\" newline=\"a\\nb\" \
+     none=null number=1.5 plain=a\\b quote=\"say \\\"hi\\\"\" space=\"a b\""
+  );
+}
+
+#[test]
+fn writes_timestamp_first() {
+  let context = Context::new();
+  let record = Record::new(Warn, "x", &context);
+  let line = formatters::Logfmt::new().format(&record);
+  let pairs = decode(&line);
+  assert_eq!(pairs[0].0, "ts");
+  assert_eq!(pairs[0].1, record.timestamp().to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+  assert_eq!(&pairs[1..], &[("level".to_string(), "warn".to_string()), ("msg".to_string(), "x".to_string())]);
+}
+
+#[test]
+fn prefixes_context_keys_named_like_fields() {
+  let context = context!{ ts: "yesterday", level: 3, msg: "inner", message: "kept" };
+  let line = formatters::Logfmt::new().format(&Record::new(Warn, "outer", &context));
+  let keys: Vec<_> = decode(&line).into_iter().map(|pair| pair.0).collect();
+  assert_eq!(keys, ["ts", "level", "msg", "ctx.level", "message", "ctx.msg", "ctx.ts"]);
+  assert!(line.ends_with(" level=warn msg=outer ctx.level=3 message=kept ctx.msg=inner ctx.ts=yesterday"));
+}
+
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn target_writes_to_any_writer() {
+  let buffer = Shared::default();
+  let logger = Logger::new(Logfmt::new(buffer.clone()), context!{ src: "db" });
+  logger.info("one");
+  logger.error("two words");
+
+  let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+  let lines: Vec<_> = output.lines().map(decode).collect();
+  assert_eq!(lines.len(), 2);
+  assert_eq!(lines[0][1..], [
+    ("level".to_string(), "info".to_string()),
+    ("msg".to_string(), "one".to_string()),
+    ("src".to_string(), "db".to_string()),
+  ]);
+  assert_eq!(lines[1][2].1, "two words");
+}
+
+proptest! {
+  #[test]
+  fn strings_round_trip(message in any::<String>(), value in any::<String>()) {
+    let mut context = Context::new();
+    context.set("key", value.clone());
+    let line = format(&message, &context);
+    prop_assert!(!line.contains('\n'));
+
+    let pairs = decode(&line);
+    prop_assert_eq!(&pairs[1], &("msg".to_string(), message));
+    if !value.is_empty() {
+      prop_assert_eq!(&pairs[2], &("key".to_string(), value));
+    }
+  }
+}
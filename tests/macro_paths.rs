@@ -0,0 +1,26 @@
+//! Checks that Quil's macros can be called by path alongside the `log`
+//! crate's macros of the same names.
+
+#![cfg(feature = "log")]
+
+extern crate log;
+extern crate quil;
+
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::{info, warn};
+
+#[test]
+fn calls_quil_and_log_macros_by_path() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), quil::context!{});
+
+  info!(logger, "imported {}", 1);
+  quil::warn!(logger, "by path"; attempt = 2);
+  quil::log!(logger, Debug, "with a level");
+  warn!(logger, "imported warn");
+  log::info!("to log, which has no logger installed");
+
+  let messages: Vec<_> = dump.dump_messages().into_iter().map(|m| m.1).collect();
+  assert_eq!(messages, ["imported 1", "by path", "with a level", "imported warn"]);
+}
@@ -0,0 +1,69 @@
+//! Checks that panics are logged by the panic hook. Everything runs in one
+//! test as the panic hook is shared by the whole test binary.
+
+#[macro_use]
+extern crate quil;
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use quil::prelude::*;
+use quil::targets::{AsyncTarget, Dump, Target};
+use quil::{Error as QuilError, PanicHookOptions, Record};
+
+struct Panics;
+
+impl Target for Panics {
+  fn log(&mut self, _: &Record) -> Result<(), QuilError> {
+    panic!("target failed");
+  }
+}
+
+#[test]
+fn panics_are_logged() {
+  // Logged and flushed through an async target, without a backtrace.
+  let dump = Dump::new();
+  quil::install_panic_hook(Logger::new(AsyncTarget::new(dump.clone()), context!{ src: "app" }));
+  let line = line!() + 1;
+  let result = thread::Builder::new().name("worker".into()).spawn(|| panic!("bad {}", 42)).unwrap().join();
+  assert!(result.is_err());
+
+  let messages = dump.dump_messages();
+  assert_eq!(messages.len(), 1);
+  let (level, ref message, ref context) = messages[0];
+  assert_eq!((level, &message[..]), (Error, "bad 42"));
+  assert_eq!(context.get("src").unwrap(), "app");
+  assert_eq!(context.get("thread").unwrap(), "worker");
+  assert!(context.get("location").unwrap().as_str().unwrap().starts_with(&format!("tests/panic_hook.rs:{}:", line)));
+  if std::env::var_os("RUST_BACKTRACE").is_none() && std::env::var_os("RUST_LIB_BACKTRACE").is_none() {
+    assert!(context.get("backtrace").is_none());
+  }
+
+  // Chained to the previous hook, with a backtrace.
+  let previous_calls = Arc::new(AtomicUsize::new(0));
+  let calls = previous_calls.clone();
+  panic::set_hook(Box::new(move |_| {
+    calls.fetch_add(1, Ordering::SeqCst);
+  }));
+  let dump = Dump::new();
+  PanicHookOptions::new()
+    .backtrace(true)
+    .chain(true)
+    .install(Logger::new(dump.clone(), context!{}));
+  assert!(panic::catch_unwind(|| panic!("with backtrace")).is_err());
+
+  let messages = dump.dump_messages();
+  assert_eq!(messages[0].1, "with backtrace");
+  assert!(messages[0].2.get("backtrace").is_some());
+  assert_eq!(previous_calls.load(Ordering::SeqCst), 1);
+
+  // A panic inside the logger's own target is passed to the previous hook
+  // rather than logged.
+  let logger = Logger::new(Panics, context!{});
+  PanicHookOptions::new().chain(true).install(logger.clone());
+  assert!(panic::catch_unwind(|| logger.info("x")).is_err());
+  assert_eq!(previous_calls.load(Ordering::SeqCst), 2);
+
+  let _ = panic::take_hook();
+}
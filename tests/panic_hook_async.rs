@@ -0,0 +1,49 @@
+//! Checks that a panic on an async target's worker thread does not deadlock
+//! the panic hook. Kept apart from `panic_hook.rs` as the panic hook is
+//! shared by the whole test binary.
+
+#[macro_use]
+extern crate quil;
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use quil::prelude::*;
+use quil::targets::{AsyncTarget, Target};
+use quil::{Error as QuilError, PanicHookOptions, Record};
+
+// Panics after a delay, so the logging thread is already waiting in a flush
+// holding the logger's lock.
+struct SlowPanics;
+
+impl Target for SlowPanics {
+  fn log(&mut self, _: &Record) -> Result<(), QuilError> {
+    thread::sleep(Duration::from_millis(50));
+    panic!("target failed");
+  }
+}
+
+#[test]
+fn panics_on_the_worker_are_not_logged() {
+  let previous_calls = Arc::new(AtomicUsize::new(0));
+  let calls = previous_calls.clone();
+  panic::set_hook(Box::new(move |_| {
+    calls.fetch_add(1, Ordering::SeqCst);
+  }));
+  let logger = Logger::new(AsyncTarget::new(SlowPanics), context!{});
+  PanicHookOptions::new().chain(true).install(logger.clone());
+
+  let (done, finished) = mpsc::channel();
+  thread::spawn(move || {
+    logger.info("boom");
+    let _ = done.send(logger.flush());
+  });
+  let flushed = finished.recv_timeout(Duration::from_secs(5)).expect("flush deadlocked");
+  assert!(flushed.is_err());
+  assert_eq!(previous_calls.load(Ordering::SeqCst), 1);
+
+  let _ = panic::take_hook();
+}
@@ -0,0 +1,48 @@
+//! Checks that `Logger::reconfigure` swaps the level, filter and target
+//! together, including for a message admitted just before the swap.
+
+#[macro_use]
+extern crate quil;
+
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::Filter;
+
+fn messages(dump: &Dump) -> Vec<String> {
+  dump.dump_messages().into_iter().map(|message| message.1).collect()
+}
+
+#[test]
+fn swaps_level_filter_and_target() {
+  let old = Dump::new();
+  let logger = Logger::new(old.clone(), context!{ src: "db" });
+  logger.set_level(Info);
+
+  let new = Dump::new();
+  logger.reconfigure(Warn, Some(Filter::parse("db=debug").unwrap()), new.clone());
+  logger.debug("query");
+  logger.ctx(context!{ src: "http" }).info("request");
+
+  assert!(old.dump_messages().is_empty());
+  assert_eq!(messages(&new), ["query"]);
+  assert_eq!(logger.level(), Warn);
+}
+
+#[test]
+fn checks_messages_admitted_before_the_swap_again() {
+  let old = Dump::new();
+  let logger = Logger::new(old.clone(), context!{});
+
+  // Admitted at the old level, which the new level rejects.
+  let admitted = logger.admit(Debug, None, None).unwrap();
+  let new = Dump::new();
+  logger.reconfigure(Info, None, new.clone());
+  logger.log_admitted(admitted, "stale", None, None, None);
+
+  // Admitted under the current level, so not checked again.
+  let admitted = logger.admit(Info, None, None).unwrap();
+  logger.log_admitted(admitted, "current", None, None, None);
+
+  assert!(old.dump_messages().is_empty());
+  assert_eq!(messages(&new), ["current"]);
+}
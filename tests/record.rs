@@ -0,0 +1,66 @@
+//! Checks that records carry the values captured when they were logged.
+
+#[macro_use]
+extern crate quil;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use quil::prelude::*;
+use quil::targets::{AsyncTarget, Target};
+use quil::{Error, Record};
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<String>>>);
+
+impl Target for Capture {
+  fn log(&mut self, record: &Record) -> Result<(), Error> {
+    self.0.lock().unwrap().push(format!(
+      "{} {} {:?} {:?} {:?} {:?}",
+      record.level(),
+      record.message(),
+      record.thread_name(),
+      record.file(),
+      record.line(),
+      record.module(),
+    ));
+    Ok(())
+  }
+}
+
+#[test]
+fn macros_record_location() {
+  let capture = Capture::default();
+  let logger = Logger::new(capture.clone(), context!{});
+
+  info!(logger, "hello {}", 1); let line = line!();
+  logger.warn("plain");
+
+  let lines = capture.0.lock().unwrap();
+  assert_eq!(
+    lines[0],
+    format!(
+      "info hello 1 Some(\"macros_record_location\") Some(\"tests/record.rs\") Some({}) Some(\"record\")",
+      line
+    )
+  );
+  assert_eq!(lines[1], "warn plain Some(\"macros_record_location\") None None None");
+}
+
+#[test]
+fn async_target_keeps_logging_thread() {
+  let capture = Capture::default();
+  let target = AsyncTarget::new(capture.clone());
+  let handle = target.handle();
+  let logger = Logger::new(target, context!{});
+
+  thread::Builder::new()
+    .name("worker-1".to_string())
+    .spawn(move || logger.info("from worker"))
+    .unwrap()
+    .join()
+    .unwrap();
+  handle.flush().unwrap();
+
+  let lines = capture.0.lock().unwrap();
+  assert_eq!(lines[0], "info from worker Some(\"worker-1\") None None None");
+}
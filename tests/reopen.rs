@@ -0,0 +1,114 @@
+//! Checks that file targets start a new file at their path when reopened
+//! after an external tool renames the old one.
+
+#[macro_use]
+extern crate quil;
+#[cfg(all(feature = "signals", unix))]
+extern crate signal_hook;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use quil::formatters::Text;
+use quil::prelude::*;
+use quil::targets::{AsyncTarget, JsonFileOptions, RollingFileOptions};
+
+fn temp_path(name: &str) -> PathBuf {
+  let path = env::temp_dir().join(format!("quil-reopen-{}-{}", std::process::id(), name));
+  let _ = fs::remove_file(&path);
+  path
+}
+
+fn rotated(path: &Path) -> PathBuf {
+  let mut rotated = path.as_os_str().to_owned();
+  rotated.push(".1");
+  let rotated = PathBuf::from(rotated);
+  let _ = fs::remove_file(&rotated);
+  rotated
+}
+
+#[test]
+fn reopens_every_file_in_a_target_set() {
+  let json = temp_path("set.json");
+  let rolling = temp_path("set.log");
+  let (json_rotated, rolling_rotated) = (rotated(&json), rotated(&rolling));
+
+  let json_target = JsonFileOptions::new(json.clone())
+    .line_flush(false)
+    .build()
+    .unwrap();
+  let rolling_target = RollingFileOptions::new(rolling.clone())
+    .formatter(Text::new().show_timestamp(false))
+    .build()
+    .unwrap();
+  let logger = Logger::new(
+    targets![AsyncTarget::new(json_target), rolling_target],
+    context!{},
+  );
+
+  logger.info("before");
+  fs::rename(&json, &json_rotated).unwrap();
+  fs::rename(&rolling, &rolling_rotated).unwrap();
+  logger.info("queued");
+  logger.reopen().unwrap();
+  logger.info("after");
+  logger.flush().unwrap();
+
+  let old = fs::read_to_string(&json_rotated).unwrap();
+  assert!(old.contains("before") && old.contains("queued"));
+  assert!(!old.contains("after"));
+  assert!(fs::read_to_string(&json).unwrap().contains("after"));
+
+  let old = fs::read_to_string(&rolling_rotated).unwrap();
+  assert!(old.contains("before") && old.contains("queued"));
+  let new = fs::read_to_string(&rolling).unwrap();
+  assert!(new.starts_with("info:    after") && !new.contains("before"));
+
+  for path in &[json, rolling, json_rotated, rolling_rotated] {
+    let _ = fs::remove_file(path);
+  }
+}
+
+#[test]
+fn keeps_the_old_file_if_reopening_fails() {
+  let dir = env::temp_dir().join(format!("quil-reopen-{}-dir", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  let path = dir.join("app.json");
+
+  let logger = Logger::new(JsonFileOptions::new(path.clone()).build().unwrap(), context!{});
+  fs::remove_dir_all(&dir).unwrap();
+  assert!(logger.reopen().is_err());
+  logger.info("still logging");
+  logger.flush().unwrap();
+}
+
+#[cfg(all(feature = "signals", unix))]
+#[test]
+fn reopens_on_sigusr1() {
+  use std::thread;
+  use std::time::{Duration, Instant};
+
+  let path = temp_path("signal.json");
+  let path_rotated = rotated(&path);
+  let logger = Logger::new(JsonFileOptions::new(path.clone()).build().unwrap(), context!{});
+  let listener = quil::ReopenOnSignalOptions::new()
+    .sighup(false)
+    .listen(logger.clone())
+    .unwrap();
+
+  fs::rename(&path, &path_rotated).unwrap();
+  signal_hook::low_level::raise(signal_hook::consts::SIGUSR1).unwrap();
+
+  let start = Instant::now();
+  while !path.exists() {
+    assert!(start.elapsed() < Duration::from_secs(5), "timed out waiting for a reopen");
+    thread::sleep(Duration::from_millis(5));
+  }
+  logger.info("after");
+  assert!(fs::read_to_string(&path).unwrap().contains("after"));
+
+  drop(listener);
+  let _ = fs::remove_file(&path);
+  let _ = fs::remove_file(&path_rotated);
+}
@@ -0,0 +1,130 @@
+//! Checks when `RollingFile` rotates, how archives are named and which
+//! archives are kept.
+
+#[macro_use]
+extern crate quil;
+extern crate chrono;
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Local};
+use quil::formatters::Text;
+use quil::prelude::*;
+use quil::targets::{Period, RollingFileOptions, Target};
+use quil::Record;
+
+fn temp_dir(name: &str) -> PathBuf {
+  let dir = env::temp_dir().join(format!("quil-rolling-{}-{}", std::process::id(), name));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+fn options(path: &Path) -> RollingFileOptions {
+  RollingFileOptions::new(path).formatter(Text::new().show_timestamp(false))
+}
+
+// Returns the archives in the directory, oldest first, with their contents.
+fn archives(dir: &Path) -> Vec<(String, String)> {
+  let mut archives: Vec<_> = fs::read_dir(dir)
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.file_name().unwrap() != "app.log")
+    .map(|path| {
+      let contents = fs::read_to_string(&path).unwrap();
+      (path.file_name().unwrap().to_string_lossy().into_owned(), contents)
+    })
+    .collect();
+  archives.sort_by(|a, b| a.1.cmp(&b.1));
+  archives
+}
+
+#[test]
+fn rotates_by_size() {
+  let dir = temp_dir("size");
+  let path = dir.join("app.log");
+  let logger = Logger::new(options(&path).max_size(50).build().unwrap(), context!{});
+
+  for message in &["first line", "second line", "third line"] {
+    logger.info(message);
+  }
+
+  let archives = archives(&dir);
+  assert_eq!(archives.len(), 1);
+  assert_eq!(archives[0].1, "info:    first line \ninfo:    second line \n");
+  assert_eq!(fs::read_to_string(&path).unwrap(), "info:    third line \n");
+  let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn never_overwrites_an_archive() {
+  let dir = temp_dir("collision");
+  let path = dir.join("app.log");
+  let mut target = options(&path).max_size(1).build().unwrap();
+
+  // Every line has the same timestamp, so every archive has the same name
+  // before its counter.
+  let context = Context::new();
+  let record = Record::new(Info, "line", &context);
+  for _ in 0..4 {
+    target.log(&record).unwrap();
+  }
+
+  let names: Vec<_> = archives(&dir).into_iter().map(|archive| archive.0).collect();
+  assert_eq!(names.len(), 3);
+  let base = names.iter().min_by_key(|name| name.len()).unwrap().clone();
+  let stem = base.trim_end_matches(".log");
+  for counter in 1..3 {
+    assert!(names.contains(&format!("{}-{}.log", stem, counter)));
+  }
+  let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn names_period_archives_after_the_closed_period() {
+  let dir = temp_dir("period");
+  let path = dir.join("app.log");
+  fs::write(&path, "info:    yesterday \n").unwrap();
+  let yesterday = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+  OpenOptions::new()
+    .write(true)
+    .open(&path)
+    .unwrap()
+    .set_modified(yesterday)
+    .unwrap();
+
+  let logger = Logger::new(options(&path).period(Period::Daily).build().unwrap(), context!{});
+  logger.info("today");
+
+  let archives = archives(&dir);
+  assert_eq!(archives.len(), 1);
+  let yesterday: DateTime<Local> = yesterday.into();
+  assert!(archives[0].0.starts_with(&yesterday.format("app.%Y%m%d-").to_string()));
+  assert_eq!(archives[0].1, "info:    yesterday \n");
+  assert_eq!(fs::read_to_string(&path).unwrap(), "info:    today \n");
+  let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn keeps_only_the_newest_archives() {
+  let dir = temp_dir("keep");
+  let path = dir.join("app.log");
+  let mut target = options(&path).max_size(1).keep(2).build().unwrap();
+
+  // Archives sharing a timestamp are ordered by their counter.
+  let context = Context::new();
+  let record = Record::new(Info, "same", &context);
+  for _ in 0..3 {
+    target.log(&record).unwrap();
+  }
+  for message in &["a", "b", "c"] {
+    target.log(&Record::new(Info, message, &context)).unwrap();
+  }
+
+  let contents: Vec<_> = archives(&dir).into_iter().map(|archive| archive.1).collect();
+  assert_eq!(contents, ["info:    a \n", "info:    b \n"]);
+  assert_eq!(fs::read_to_string(&path).unwrap(), "info:    c \n");
+  let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,133 @@
+//! Checks that scoped contexts are merged into messages.
+
+#[macro_use]
+extern crate quil;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::thread;
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::Filter;
+
+fn keys(dump: &Dump) -> Vec<Vec<(String, String)>> {
+  dump
+    .dump_messages()
+    .into_iter()
+    .map(|(_, _, context)| {
+      let mut pairs: Vec<_> = context.into_iter().map(|(k, v)| (k, v.to_string())).collect();
+      pairs.sort();
+      pairs
+    })
+    .collect()
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+  pairs.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn nested_scopes_merge() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{ src: "api" });
+
+  quil::with_context(context!{ request_id: "r1", user: "jane" }, || {
+    logger.info("outer");
+    quil::with_context(context!{ user: "john", step: "2" }, || {
+      info!(logger, "inner"; step = "3");
+    });
+    logger.info("after");
+  });
+  logger.info("outside");
+
+  assert_eq!(keys(&dump), [
+    pairs(&[("request_id", "r1"), ("src", "api"), ("user", "jane")]),
+    pairs(&[("request_id", "r1"), ("src", "api"), ("step", "3"), ("user", "john")]),
+    pairs(&[("request_id", "r1"), ("src", "api"), ("user", "jane")]),
+    pairs(&[("src", "api")]),
+  ]);
+}
+
+#[test]
+fn logger_context_takes_precedence() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{ src: "api" });
+  let _guard = quil::push_context(context!{ src: "scope", id: 1 });
+  logger.info("x");
+
+  assert_eq!(keys(&dump), [pairs(&[("id", "1"), ("src", "api")])]);
+}
+
+#[test]
+fn guards_end_scopes() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  let outer = quil::push_context(context!{ a: 1 });
+  let _inner = quil::push_context(context!{ b: 2 });
+  logger.info("both");
+  drop(outer);
+  logger.info("none");
+
+  assert_eq!(keys(&dump), [pairs(&[("a", "1"), ("b", "2")]), vec![]]);
+}
+
+#[test]
+fn scopes_are_per_thread() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  let _guard = quil::push_context(context!{ a: 1 });
+  let other = logger.clone();
+  thread::spawn(move || other.info("other thread")).join().unwrap();
+
+  assert_eq!(keys(&dump), [vec![]]);
+}
+
+#[test]
+fn filters_see_scoped_context() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  logger.set_filter(Filter::parse("warn,db=trace").unwrap());
+
+  logger.debug("dropped");
+  quil::with_context(context!{ src: "db" }, || logger.debug("kept"));
+
+  assert_eq!(keys(&dump), [pairs(&[("src", "db")])]);
+}
+
+/// Logs once each time it is polled, returning pending the first time.
+struct Steps {
+  logger: Logger,
+  polls: usize,
+}
+
+impl Future for Steps {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, _: &mut TaskContext) -> Poll<()> {
+    self.polls += 1;
+    self.logger.info("poll");
+    if self.polls == 1 { Poll::Pending } else { Poll::Ready(()) }
+  }
+}
+
+#[test]
+fn futures_carry_context_between_polls() {
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  let mut task = Box::pin(quil::with_context_async(
+    context!{ request_id: "r2" },
+    Steps { logger: logger.clone(), polls: 0 },
+  ));
+
+  let mut cx = TaskContext::from_waker(Waker::noop());
+  assert!(task.as_mut().poll(&mut cx).is_pending());
+  logger.info("between");
+  thread::spawn(move || {
+    let mut cx = TaskContext::from_waker(Waker::noop());
+    assert!(task.as_mut().poll(&mut cx).is_ready());
+  }).join().unwrap();
+
+  let expected = pairs(&[("request_id", "r2")]);
+  assert_eq!(keys(&dump), [expected.clone(), vec![], expected]);
+}
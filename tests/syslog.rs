@@ -0,0 +1,156 @@
+//! Checks the exact frames the `Syslog` target sends to local Unix
+//! datagram, UDP and TCP listeners.
+
+#[macro_use]
+extern crate quil;
+extern crate chrono;
+
+use std::io::Read;
+use std::net::{TcpListener, UdpSocket};
+use chrono::{Local, SecondsFormat};
+use quil::prelude::*;
+use quil::targets::{Facility, Framing, Syslog, SyslogFormat, SyslogOptions, Target, Transport};
+use quil::{Level, Record};
+
+fn options(transport: Transport) -> SyslogOptions {
+  SyslogOptions::new()
+    .transport(transport)
+    .facility(Facility::Local0)
+    .hostname("web1")
+    .app_name("api")
+    .procid("42")
+    .sd_id("quil@32473")
+}
+
+fn rfc5424_timestamp(record: &Record) -> String {
+  record
+    .timestamp()
+    .with_timezone(&Local)
+    .to_rfc3339_opts(SecondsFormat::Micros, false)
+}
+
+fn rfc3164_timestamp(record: &Record) -> String {
+  record.timestamp().with_timezone(&Local).format("%b %e %H:%M:%S").to_string()
+}
+
+// Logs a record and returns its RFC 5424 timestamp.
+fn log(syslog: &mut Syslog, level: Level, message: &str, context: &Context) -> String {
+  let record = Record::new(level, message, context);
+  syslog.log(&record).unwrap();
+  rfc5424_timestamp(&record)
+}
+
+#[cfg(unix)]
+#[test]
+fn sends_rfc5424_over_a_unix_datagram_socket() {
+  use std::os::unix::net::UnixDatagram;
+
+  let path = std::env::temp_dir().join(format!("quil-syslog-{}.sock", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+  let server = UnixDatagram::bind(&path).unwrap();
+  let mut syslog = options(Transport::Unix(path.clone())).build().unwrap();
+
+  let mut context = context!{ src: "root" };
+  context.set("bad key", "a\"b]c\\d");
+  let timestamp = log(&mut syslog, Warn, "disk full", &context);
+  let empty = log(&mut syslog, Trace, "tick", &context!{});
+
+  let mut buf = [0; 1024];
+  let len = server.recv(&mut buf).unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&buf[..len]),
+    format!(
+      "<132>1 {} web1 api 42 - [quil@32473 bad_key=\"a\\\"b\\]c\\\\d\" src=\"root\"] disk full",
+      timestamp
+    )
+  );
+  let len = server.recv(&mut buf).unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&buf[..len]),
+    format!("<135>1 {} web1 api 42 - - tick", empty)
+  );
+  let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn appends_the_context_without_an_sd_id() {
+  let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let mut syslog = SyslogOptions::new()
+    .transport(Transport::Udp(server.local_addr().unwrap()))
+    .hostname("web1")
+    .app_name("api")
+    .procid("42")
+    .build()
+    .unwrap();
+
+  let timestamp = log(&mut syslog, Info, "retrying", &context!{ src: "root", attempt: 2 });
+
+  let mut buf = [0; 1024];
+  let len = server.recv(&mut buf).unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&buf[..len]),
+    format!("<14>1 {} web1 api 42 - - retrying attempt=2 src=root", timestamp)
+  );
+}
+
+#[test]
+fn sends_rfc3164_over_udp() {
+  let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let mut syslog = options(Transport::Udp(server.local_addr().unwrap()))
+    .format(SyslogFormat::Rfc3164)
+    .build()
+    .unwrap();
+
+  let context = context!{ src: "root", attempt: 2 };
+  let record = Record::new(Verbose, "retrying", &context);
+  syslog.log(&record).unwrap();
+
+  let mut buf = [0; 1024];
+  let len = server.recv(&mut buf).unwrap();
+  assert_eq!(
+    String::from_utf8_lossy(&buf[..len]),
+    format!("<134>{} web1 api[42]: retrying attempt=2 src=root", rfc3164_timestamp(&record))
+  );
+}
+
+fn receive_tcp(framing: Framing) -> (String, Vec<String>) {
+  let server = TcpListener::bind("127.0.0.1:0").unwrap();
+  let mut syslog = options(Transport::Tcp(server.local_addr().unwrap()))
+    .framing(framing)
+    .build()
+    .unwrap();
+
+  let first = log(&mut syslog, Error, "disk full", &context!{});
+  let second = log(&mut syslog, Info, "two\nlines", &context!{ src: "root" });
+  drop(syslog);
+
+  let mut received = String::new();
+  server.accept().unwrap().0.read_to_string(&mut received).unwrap();
+  (received, vec![first, second])
+}
+
+#[test]
+fn frames_tcp_messages_with_octet_counting() {
+  let (received, timestamps) = receive_tcp(Framing::OctetCounting);
+  let first = format!("<131>1 {} web1 api 42 - - disk full", timestamps[0]);
+  let second = format!(
+    "<134>1 {} web1 api 42 - [quil@32473 src=\"root\"] two\nlines",
+    timestamps[1]
+  );
+  assert_eq!(
+    received,
+    format!("{} {}{} {}", first.len(), first, second.len(), second)
+  );
+}
+
+#[test]
+fn frames_tcp_messages_with_newlines() {
+  let (received, timestamps) = receive_tcp(Framing::Newline);
+  assert_eq!(
+    received,
+    format!(
+      "<131>1 {} web1 api 42 - - disk full\n<134>1 {} web1 api 42 - [quil@32473 src=\"root\"] two lines\n",
+      timestamps[0], timestamps[1]
+    )
+  );
+}
@@ -0,0 +1,97 @@
+//! Checks parsing and rendering of `Template` formatters.
+
+#[macro_use]
+extern crate quil;
+
+use quil::formatters::{Formatter, Template, Theme, Timezone};
+use quil::prelude::*;
+use quil::Record;
+
+fn render(template: &str, level: quil::Level, message: &str, context: &Context) -> String {
+  Template::parse(template)
+    .unwrap()
+    .format(&Record::new(level, message, context))
+}
+
+#[test]
+fn pads_and_truncates() {
+  let context = context!{ src: "db", user: "ünïcödé" };
+  assert_eq!(render("[{level:<7}]", Info, "", &context), "[info   ]");
+  assert_eq!(render("[{level:>7}]", Info, "", &context), "[   info]");
+  assert_eq!(render("[{level:^8}]", Info, "", &context), "[  info  ]");
+  assert_eq!(render("[{ctx.user:.3}]", Info, "", &context), "[ünï]");
+  assert_eq!(render("[{ctx.user:5.3}]", Info, "", &context), "[ünï  ]");
+  assert_eq!(render("[{ctx.missing:3}]", Info, "", &context), "[   ]");
+}
+
+#[test]
+fn renders_context() {
+  let context = context!{ src: "db", b: 2, a: true };
+  assert_eq!(render("{message} {ctx}", Info, "hi", &context), "hi a=true b=2 src=db");
+  assert_eq!(render("{ctx.src}: {message} {ctx:rest}", Info, "hi", &context), "db: hi a=true b=2");
+  assert_eq!(render("{message} {ctx:rest}", Info, "hi", &Context::new()), "hi");
+}
+
+#[test]
+fn renders_escaped_braces_and_location() {
+  let context = Context::new();
+  let record = Record::new(Debug, "x", &context).location(Some("src/main.rs"), Some(12), Some("app"));
+  let template = Template::parse("{{{file}:{line}}} {module} {thread} {level}").unwrap();
+  assert_eq!(template.format(&record), "{src/main.rs:12} app renders_escaped_braces_and_location debug");
+}
+
+#[test]
+fn renders_time() {
+  let context = Context::new();
+  let record = Record::new(Info, "x", &context);
+  let template = Template::parse("{time:%Y-%m-%dT%H:%M:%S%.3f}").unwrap().timezone(Timezone::Utc);
+  assert_eq!(template.format(&record), record.timestamp().format("%Y-%m-%dT%H:%M:%S%.3f").to_string());
+}
+
+#[test]
+fn escapes_bars_in_time_formats() {
+  let context = Context::new();
+  let record = Record::new(Info, "x", &context);
+  let expected = record.timestamp().format("%H|%M|%S").to_string();
+
+  let template = Template::parse("{time:%H||%M||%S}").unwrap().timezone(Timezone::Utc);
+  assert_eq!(template.format(&record), expected);
+
+  let template = Template::parse("{time:%H||%M||%S|bold}").unwrap().timezone(Timezone::Utc);
+  assert_eq!(template.format(&record), expected);
+  let template = template.colored(true);
+  assert_eq!(template.format(&record), format!("\u{1b}[1m{}\u{1b}[0m", expected));
+}
+
+#[test]
+fn applies_styles_only_when_colored() {
+  let context = Context::new();
+  let record = Record::new(Error, "boom", &context);
+
+  let template = Template::parse("{level|level} {message|bold on_blue}").unwrap();
+  assert_eq!(template.format(&record), "error boom");
+
+  let template = template.colored(true);
+  assert_eq!(template.format(&record), "\u{1b}[1;91merror\u{1b}[0m \u{1b}[1;44mboom\u{1b}[0m");
+
+  let template = template.theme(Theme::monochrome());
+  assert_eq!(template.format(&record), "\u{1b}[1merror\u{1b}[0m \u{1b}[1;44mboom\u{1b}[0m");
+}
+
+#[test]
+fn rejects_invalid_templates() {
+  let cases = [
+    ("{level", 0, "unclosed `{`"),
+    ("a }", 2, "unmatched `}`"),
+    ("x {nope}", 2, "unknown field `nope`"),
+    ("{level:>x}", 0, "invalid format spec `>x`"),
+    ("{ctx:all}", 0, "unknown context spec `all`"),
+    ("{time:%Q}", 0, "invalid time format `%Q`"),
+    ("{message|sparkly}", 0, "unknown colour `sparkly`"),
+  ];
+  for &(template, position, reason) in &cases {
+    let error = Template::parse(template).unwrap_err();
+    assert_eq!(error.position(), position, "{}", template);
+    assert_eq!(error.to_string(), format!("invalid template at {}: {}", position, reason));
+  }
+}
@@ -0,0 +1,56 @@
+//! Checks built-in and parsed themes and their use by `Text`.
+
+#[macro_use]
+extern crate quil;
+
+use quil::formatters::{Color, Formatter, Style, Text, Theme};
+use quil::prelude::*;
+use quil::Record;
+
+#[test]
+fn parses_entries_over_base_theme() {
+  let theme = Theme::parse("warn = underline yellow on_black; base = monochrome\n# comment\n\ntimestamp = none").unwrap();
+  assert_eq!(theme.level_style(Warn), Style::new().fg(Color::Yellow).bg(Color::Black).underline());
+  assert_eq!(theme.level_style(Error), Theme::monochrome().level_style(Error));
+  assert_eq!(theme.timestamp_style(), Style::new());
+  assert_eq!(Theme::parse("").unwrap(), Theme::dark());
+}
+
+#[test]
+fn rejects_invalid_themes() {
+  let cases = [
+    ("info", 1, "expected `name = style`, found `info`"),
+    ("info = blue\nbase = sepia", 2, "unknown base theme `sepia`"),
+    ("info = blue\n\nfatal = red", 3, "unknown theme entry `fatal`"),
+    ("key = on_sparkly", 1, "unknown colour `sparkly`"),
+  ];
+  for &(config, line, reason) in &cases {
+    let error = Theme::parse(config).unwrap_err();
+    assert_eq!(error.line(), line, "{}", config);
+    assert_eq!(error.to_string(), format!("invalid theme on line {}: {}", line, reason));
+  }
+}
+
+#[test]
+fn rejects_invalid_styles() {
+  let error = Style::parse("bold on_sparkly").unwrap_err();
+  assert_eq!(error.word(), "sparkly");
+  assert_eq!(error.to_string(), "unknown colour `sparkly`");
+  let error: Box<dyn std::error::Error> = Box::new(Style::parse("glowing").unwrap_err());
+  assert_eq!(error.to_string(), "unknown colour `glowing`");
+  assert_eq!(Style::parse("none").unwrap(), Style::new());
+}
+
+#[test]
+fn text_paints_with_theme() {
+  let theme = Theme::parse("info = green; key = bold; value = italic").unwrap();
+  let context = context!{ src: "db" };
+  let record = Record::new(Info, "hi", &context);
+
+  let text = Text::new().show_timestamp(false).theme(theme);
+  assert_eq!(text.format(&record), "info:    hi src=db");
+  assert_eq!(
+    text.colored(true).format(&record),
+    "\u{1b}[32minfo\u{1b}[0m:    hi \u{1b}[1msrc\u{1b}[0m=\u{1b}[3mdb\u{1b}[0m"
+  );
+}
@@ -0,0 +1,114 @@
+//! Checks what `TracingLayer` logs for `tracing` events and spans.
+
+#![cfg(feature = "tracing")]
+
+#[macro_use]
+extern crate quil;
+#[macro_use]
+extern crate tracing;
+extern crate tracing_subscriber;
+
+use quil::prelude::*;
+use quil::targets::Dump;
+use quil::tracing_layer::TracingLayer;
+use quil::Level;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn run<F>(span_events: bool, f: F) -> Vec<(Level, String, Context)>
+where
+  F: FnOnce(),
+{
+  let dump = Dump::new();
+  let logger = Logger::new(dump.clone(), context!{});
+  logger.set_level(Trace);
+  let layer = TracingLayer::new(logger).span_events(span_events);
+  tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), f);
+  dump.dump_messages()
+}
+
+#[test]
+fn event_fields_reach_the_context() {
+  let messages = run(false, || {
+    warn!(user = "bob", attempts = 3u64, admin = false, "logged in");
+  });
+
+  assert_eq!(messages.len(), 1);
+  let (level, ref message, ref context) = messages[0];
+  assert_eq!(level, Warn);
+  assert_eq!(message, "logged in");
+  assert_eq!(context.get("user").unwrap(), "bob");
+  assert_eq!(context.get("attempts"), Some(&Value::U64(3)));
+  assert_eq!(context.get("admin"), Some(&Value::Bool(false)));
+  assert!(context.get("message").is_none());
+  assert_eq!(context.get("module").unwrap(), module_path!());
+}
+
+#[test]
+fn span_fields_are_flattened_into_child_events() {
+  let messages = run(false, || {
+    let outer = info_span!("request", id = 7i64, user = "bob");
+    let _outer = outer.enter();
+    let inner = info_span!("query", user = "alice");
+    let _inner = inner.enter();
+    info!(rows = 2u64, "done");
+  });
+
+  assert_eq!(messages.len(), 1);
+  let context = &messages[0].2;
+  assert_eq!(context.get("id"), Some(&Value::I64(7)));
+  assert_eq!(context.get("user").unwrap(), "alice");
+  assert_eq!(context.get("rows"), Some(&Value::U64(2)));
+  assert_eq!(context.get("span").unwrap(), "query");
+}
+
+#[test]
+fn recorded_span_fields_take_effect() {
+  let messages = run(false, || {
+    let span = info_span!("request", status = tracing::field::Empty);
+    let _entered = span.enter();
+    info!("before");
+    span.record("status", "ok");
+    info!("after");
+  });
+
+  assert_eq!(messages.len(), 2);
+  assert!(messages[0].2.get("status").is_none());
+  assert_eq!(messages[1].2.get("status").unwrap(), "ok");
+}
+
+#[test]
+fn span_exits_carry_durations() {
+  let messages = run(true, || {
+    let span = info_span!("request", id = 7i64);
+    span.in_scope(|| {});
+    span.in_scope(|| {});
+  });
+
+  let messages: Vec<_> = messages.iter().map(|m| (m.0, m.1.as_str(), &m.2)).collect();
+  assert_eq!(messages.len(), 4);
+  assert_eq!(messages[0].1, "enter request");
+  assert_eq!(messages[1].1, "exit request");
+  for message in &messages {
+    assert_eq!(message.0, Trace);
+    assert_eq!(message.2.get("id"), Some(&Value::I64(7)));
+    assert_eq!(message.2.get("span").unwrap(), "request");
+  }
+  assert!(messages[0].2.get("duration").is_none());
+
+  let duration = |index: usize, key: &str| match messages[index].2.get(key) {
+    Some(&Value::U64(micros)) => micros,
+    other => panic!("expected {} in microseconds, got {:?}", key, other),
+  };
+  assert_eq!(duration(1, "busy"), duration(1, "duration"));
+  // Both are rounded down to whole microseconds after being added up.
+  let total = duration(1, "busy") + duration(3, "duration");
+  assert!((total..=total + 1).contains(&duration(3, "busy")));
+}
+
+#[test]
+fn span_events_can_be_disabled() {
+  let messages = run(false, || {
+    info_span!("request").in_scope(|| {});
+  });
+  assert!(messages.is_empty());
+}
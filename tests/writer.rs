@@ -0,0 +1,114 @@
+//! Checks how `Writer` buffers, flushes and formats lines.
+
+#[macro_use]
+extern crate quil;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use quil::formatters::{Json, Text};
+use quil::prelude::*;
+use quil::targets::{Target, WriterOptions};
+
+/// A writer which records each write it receives and each flush.
+#[derive(Clone, Default)]
+struct Recorder {
+  writes: Arc<Mutex<Vec<String>>>,
+  flushes: Arc<Mutex<usize>>,
+}
+
+impl Recorder {
+  fn writes(&self) -> Vec<String> {
+    self.writes.lock().unwrap().clone()
+  }
+
+  fn flushes(&self) -> usize {
+    *self.flushes.lock().unwrap()
+  }
+}
+
+impl Write for Recorder {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.writes.lock().unwrap().push(String::from_utf8(buf.to_vec()).unwrap());
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    *self.flushes.lock().unwrap() += 1;
+    Ok(())
+  }
+}
+
+fn untimed() -> Text {
+  Text::new().show_timestamp(false)
+}
+
+#[test]
+fn flushes_each_line_whole() {
+  let recorder = Recorder::default();
+  let writer = WriterOptions::new().formatter(untimed()).build(recorder.clone());
+  let logger = Logger::new(writer, context!{ id: 1 });
+  logger.info("one");
+  logger.warn("two");
+
+  assert_eq!(recorder.writes(), ["info:    one id=1\n", "warn:    two id=1\n"]);
+  assert_eq!(recorder.flushes(), 2);
+}
+
+#[test]
+fn buffers_until_flushed() {
+  let recorder = Recorder::default();
+  let writer = WriterOptions::new()
+    .formatter(untimed().show_context(false))
+    .line_flush(false)
+    .build(recorder.clone());
+  let logger = Logger::new(writer, context!{});
+  logger.info("one");
+  logger.warn("two");
+  assert!(recorder.writes().is_empty());
+
+  logger.flush().unwrap();
+  assert_eq!(recorder.writes(), ["info:    one\nwarn:    two\n"]);
+}
+
+#[test]
+fn unbuffered_writes_through() {
+  let recorder = Recorder::default();
+  let writer = WriterOptions::new()
+    .formatter(untimed().show_context(false))
+    .buffer_capacity(0)
+    .line_flush(false)
+    .build(recorder.clone());
+  let logger = Logger::new(writer, context!{});
+  logger.info("one");
+
+  assert_eq!(recorder.writes(), ["info:    one\n"]);
+  assert_eq!(recorder.flushes(), 0);
+}
+
+#[test]
+fn strips_color() {
+  let mut writer = WriterOptions::new()
+    .formatter(untimed().show_context(false).colored(true))
+    .strip_color(true)
+    .build(Vec::new());
+  let context = Context::new();
+  writer.log(&quil::Record::new(Error, "boom", &context)).unwrap();
+
+  assert_eq!(writer.into_inner().unwrap(), b"error:   boom\n");
+}
+
+#[test]
+fn pairs_with_any_formatter() {
+  let mut writer = WriterOptions::new()
+    .formatter(Json::new().show_timestamp(false))
+    .line_flush(false)
+    .build(Vec::new());
+  let context = context!{ n: 2 };
+  writer.log(&quil::Record::new(Info, "hi", &context)).unwrap();
+
+  assert!(writer.get_ref().is_empty());
+  assert_eq!(
+    String::from_utf8(writer.into_inner().unwrap()).unwrap(),
+    "{ \"level\": \"info\", \"message\": \"hi\", \"context\": { \"n\": 2 } }\n"
+  );
+}